@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use rdkafka::ClientConfig;
+use serde::Serialize;
+
+use crate::kafka_connection::KafkaSecurity;
+
+const MANAGED_PROPERTIES: [&str; 2] = ["bootstrap.servers", "group.id"];
+
+#[derive(Serialize)]
+pub struct SkippedProperty {
+    key: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportedProperties {
+    security: KafkaSecurity,
+    properties: BTreeMap<String, String>,
+    skipped: Vec<SkippedProperty>,
+}
+
+pub fn validate_properties(properties: &BTreeMap<String, String>) -> Result<(), String> {
+    for (key, value) in properties {
+        validate_property(key, value)?;
+    }
+
+    Ok(())
+}
+
+fn validate_property(key: &str, value: &str) -> Result<(), String> {
+    if key.trim().is_empty() {
+        return Err("Property name cannot be empty".to_string());
+    }
+
+    if MANAGED_PROPERTIES.contains(&key) {
+        return Err(format!("Property {} is managed by Komprender", key));
+    }
+
+    if KafkaSecurity::is_security_property(key) {
+        return Err(format!(
+            "Property {} must be set through connection security settings",
+            key
+        ));
+    }
+
+    ClientConfig::new()
+        .set(key, value)
+        .create_native_config()
+        .map_err(|e| format!("Invalid property {}={}: {}", key, value, e))?;
+
+    Ok(())
+}
+
+pub fn import_properties(contents: &str) -> ImportedProperties {
+    let mut properties = parse_java_properties(contents);
+
+    if let Some(jaas_config) = properties.remove("sasl.jaas.config") {
+        for option in ["username", "password"] {
+            if let Some(value) = jaas_option(&jaas_config, option) {
+                properties.insert(format!("sasl.{}", option), value);
+            }
+        }
+    }
+
+    let security = KafkaSecurity::take_from_properties(&mut properties);
+
+    let mut skipped = vec![];
+    properties.retain(|key, value| match validate_property(key, value) {
+        Ok(_) => true,
+        Err(reason) => {
+            skipped.push(SkippedProperty {
+                key: key.clone(),
+                reason,
+            });
+            false
+        }
+    });
+
+    ImportedProperties {
+        security,
+        properties,
+        skipped,
+    }
+}
+
+fn parse_java_properties(contents: &str) -> BTreeMap<String, String> {
+    let mut properties = BTreeMap::new();
+    let mut logical_line = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim_start();
+
+        if logical_line.is_empty() && (line.starts_with('#') || line.starts_with('!')) {
+            continue;
+        }
+
+        let trailing_backslashes = line.chars().rev().take_while(|c| *c == '\\').count();
+        if trailing_backslashes % 2 == 1 {
+            logical_line.push_str(&line[..line.len() - 1]);
+            continue;
+        }
+
+        logical_line.push_str(line);
+        if !logical_line.is_empty() {
+            let (key, value) = split_property(&logical_line);
+            properties.insert(key, value);
+        }
+        logical_line.clear();
+    }
+
+    if !logical_line.is_empty() {
+        let (key, value) = split_property(&logical_line);
+        properties.insert(key, value);
+    }
+
+    properties
+}
+
+fn split_property(line: &str) -> (String, String) {
+    let mut key = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    key.push('\\');
+                    key.push(escaped);
+                }
+            }
+            '=' | ':' => break,
+            c if c.is_whitespace() => {
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if let Some('=') | Some(':') = chars.peek() {
+                    chars.next();
+                }
+                break;
+            }
+            c => key.push(c),
+        }
+    }
+
+    let value: String = chars.collect();
+
+    (unescape(&key), unescape(value.trim_start()))
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{000C}'),
+            Some('u') => {
+                let code: String = chars.by_ref().take(4).collect();
+                if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    result.push(c);
+                }
+            }
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+
+    result
+}
+
+fn jaas_option(jaas_config: &str, option: &str) -> Option<String> {
+    let pattern = format!("{}=", option);
+    let start = jaas_config
+        .match_indices(&pattern)
+        .find(|(index, _)| {
+            *index == 0
+                || jaas_config[..*index]
+                    .chars()
+                    .last()
+                    .map_or(false, char::is_whitespace)
+        })
+        .map(|(index, _)| index + pattern.len())?;
+    let rest = &jaas_config[start..];
+
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => value.extend(chars.next()),
+                '"' => return Some(value),
+                c => value.push(c),
+            }
+        }
+        None
+    } else {
+        Some(
+            rest.chars()
+                .take_while(|c| !c.is_whitespace() && *c != ';')
+                .collect(),
+        )
+    }
+}
+
+#[tauri::command]
+pub async fn import_client_properties(path: &str) -> Result<ImportedProperties, String> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Could not read {}: {}", path, e))?;
+
+    Ok(import_properties(&contents))
+}
+
+#[tauri::command]
+pub async fn validate_client_properties(
+    properties: BTreeMap<String, String>,
+) -> Result<(), String> {
+    validate_properties(&properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_separators_comments_and_blank_lines() {
+        let properties = parse_java_properties(concat!(
+            "# comment\n",
+            "! also a comment\n",
+            "\n",
+            "bootstrap.servers=kafka:9092\n",
+            "client.id : komprender\n",
+            "acks all\n",
+            "  linger.ms   =  5\n",
+            "empty=\n",
+        ));
+
+        assert_eq!(properties["bootstrap.servers"], "kafka:9092");
+        assert_eq!(properties["client.id"], "komprender");
+        assert_eq!(properties["acks"], "all");
+        assert_eq!(properties["linger.ms"], "5");
+        assert_eq!(properties["empty"], "");
+        assert_eq!(properties.len(), 5);
+    }
+
+    #[test]
+    fn joins_continuation_lines() {
+        let properties = parse_java_properties(concat!(
+            "sasl.jaas.config=org.apache.kafka.common.security.plain.PlainLoginModule \\\n",
+            "    required \\\n",
+            "    username=\"alice\" \\\n",
+            "    password=\"secret\";\n",
+            "# not part of it\n",
+            "next=value\\\\\n",
+            "last=1",
+        ));
+
+        assert_eq!(
+            properties["sasl.jaas.config"],
+            concat!(
+                "org.apache.kafka.common.security.plain.PlainLoginModule required ",
+                "username=\"alice\" password=\"secret\";"
+            )
+        );
+        assert_eq!(properties["next"], "value\\");
+        assert_eq!(properties["last"], "1");
+    }
+
+    #[test]
+    fn keeps_a_trailing_continuation_at_end_of_file() {
+        let properties = parse_java_properties("key=first \\");
+
+        assert_eq!(properties["key"], "first ");
+    }
+
+    #[test]
+    fn unescapes_keys_and_values() {
+        let properties =
+            parse_java_properties("my\\ key\\=x=tab\\there\nunicode=caf\\u00e9\nc\\:d=\\#literal");
+
+        assert_eq!(properties["my key=x"], "tab\there");
+        assert_eq!(properties["unicode"], "café");
+        assert_eq!(properties["c:d"], "#literal");
+    }
+
+    #[test]
+    fn reads_jaas_options() {
+        let config = "org.apache.kafka.common.security.scram.ScramLoginModule required \
+            username=\"alice\" password=\"p\\\"a ss;word\" token=plain;";
+
+        assert_eq!(jaas_option(config, "username").as_deref(), Some("alice"));
+        assert_eq!(
+            jaas_option(config, "password").as_deref(),
+            Some("p\"a ss;word")
+        );
+        assert_eq!(jaas_option(config, "token").as_deref(), Some("plain"));
+        assert_eq!(jaas_option(config, "missing"), None);
+    }
+
+    #[test]
+    fn ignores_jaas_options_that_only_end_with_the_name() {
+        let config = "LoginModule required my_password=\"wrong\" password=\"right\";";
+
+        assert_eq!(jaas_option(config, "password").as_deref(), Some("right"));
+        assert_eq!(
+            jaas_option("LoginModule required password=\"open", "password"),
+            None
+        );
+    }
+}
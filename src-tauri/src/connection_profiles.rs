@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection_store::{write_file_atomically, ConnectionItem, ConnectionStore};
+use crate::kafka_connection::KafkaSecurity;
+use crate::schema_registry::SchemaRegistrySecurity;
+
+const PROFILES_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub kafka_broker: String,
+    #[serde(default)]
+    pub schema_registry: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub security: KafkaSecurity,
+    #[serde(default)]
+    pub schema_registry_security: SchemaRegistrySecurity,
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfilesFile {
+    version: u8,
+    connections: Vec<ConnectionProfile>,
+}
+
+#[derive(Serialize)]
+pub struct ImportConflict {
+    pub name: String,
+    pub kafka_broker: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub imported: Vec<ConnectionItem>,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+enum ProfilesFormat {
+    Json,
+    Yaml,
+}
+
+impl ProfilesFormat {
+    fn from_path(path: &str) -> ProfilesFormat {
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => ProfilesFormat::Yaml,
+            _ => ProfilesFormat::Json,
+        }
+    }
+}
+
+fn serialize_profiles(
+    connections: Vec<ConnectionProfile>,
+    format: ProfilesFormat,
+) -> Result<String, String> {
+    let file = ProfilesFile {
+        version: PROFILES_VERSION,
+        connections,
+    };
+
+    match format {
+        ProfilesFormat::Json => serde_json::to_string_pretty(&file).map_err(|e| e.to_string()),
+        ProfilesFormat::Yaml => serde_yaml::to_string(&file).map_err(|e| e.to_string()),
+    }
+}
+
+fn parse_profiles(
+    contents: &str,
+    format: ProfilesFormat,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let file: ProfilesFile = match format {
+        ProfilesFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string())?,
+        ProfilesFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string())?,
+    };
+
+    if file.version != PROFILES_VERSION {
+        return Err(format!(
+            "Unsupported connection profiles version: {}",
+            file.version
+        ));
+    }
+
+    Ok(file.connections)
+}
+
+#[tauri::command]
+pub async fn export_connection_profiles(
+    ids: Vec<String>,
+    path: &str,
+    include_secrets: Option<bool>,
+) -> Result<usize, String> {
+    let profiles = ConnectionStore::export_profiles(&ids, include_secrets.unwrap_or(false)).await?;
+    let exported = profiles.len();
+    let serialized = serialize_profiles(profiles, ProfilesFormat::from_path(path))?;
+
+    write_file_atomically(Path::new(path), serialized.as_bytes()).await?;
+
+    Ok(exported)
+}
+
+#[tauri::command]
+pub async fn import_connection_profiles(path: &str) -> Result<ImportReport, String> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let profiles = parse_profiles(&contents, ProfilesFormat::from_path(path))?;
+
+    ConnectionStore::import_profiles(profiles).await
+}
@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::client_properties::validate_properties;
+use crate::connection_profiles::{ConnectionProfile, ImportConflict, ImportReport};
+use crate::credential_vault::{ConnectionSecrets, CredentialVault};
+use crate::kafka_connection::KafkaSecurity;
+use crate::schema_registry::SchemaRegistrySecurity;
+
+pub struct ConnectionStore;
+
+#[derive(Serialize, Deserialize)]
+struct Connections {
+    items: Vec<ConnectionItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConnectionItem {
+    #[serde(default)]
+    id: String,
+    kafka_broker: String,
+    schema_registry: String,
+    name: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    security: KafkaSecurity,
+    #[serde(default)]
+    schema_registry_security: SchemaRegistrySecurity,
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+    #[serde(default)]
+    has_credentials: bool,
+}
+
+impl ConnectionItem {
+    fn public(&self) -> ConnectionItem {
+        ConnectionItem {
+            has_credentials: self.has_credentials || !self.secrets().is_empty(),
+            ..self.without_secrets()
+        }
+    }
+
+    fn secrets(&self) -> ConnectionSecrets {
+        let registry_secrets = self.schema_registry_security.secrets();
+
+        ConnectionSecrets {
+            schema_registry_password: registry_secrets.schema_registry_password,
+            schema_registry_token: registry_secrets.schema_registry_token,
+            ..self.security.secrets()
+        }
+    }
+
+    fn without_secrets(&self) -> ConnectionItem {
+        ConnectionItem {
+            security: self.security.without_secrets(),
+            schema_registry_security: self.schema_registry_security.without_secrets(),
+            ..self.clone()
+        }
+    }
+
+    fn apply_secrets(&mut self, secrets: &ConnectionSecrets) {
+        self.security.apply_secrets(secrets);
+        self.schema_registry_security.apply_secrets(secrets);
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        if self.kafka_broker.trim().is_empty() {
+            return Err("Broker cannot be empty".to_string());
+        }
+
+        self.security.validate()?;
+        self.schema_registry_security.validate()?;
+        validate_properties(&self.properties)
+    }
+
+    /// Imported profiles usually come without secrets and from another machine, so those are
+    /// only checked when connecting.
+    fn validate_structure(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        if self.kafka_broker.trim().is_empty() {
+            return Err("Broker cannot be empty".to_string());
+        }
+
+        self.security.validate_structure()?;
+        self.schema_registry_security.validate_structure()?;
+        validate_properties(&self.properties)
+    }
+}
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+impl ConnectionStore {
+    /// Callers hold `STORE_LOCK`: connections saved before ids existed get one here, written
+    /// back right away so the id stays stable across reads.
+    async fn read_items() -> Result<Vec<ConnectionItem>, String> {
+        read_items_from(&get_data_file_path("brokers.json")).await
+    }
+
+    async fn write_items(items: Vec<ConnectionItem>) -> Result<(), String> {
+        write_items_to(&get_data_file_path("brokers.json"), items).await
+    }
+
+    async fn modify<T, F>(apply: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Vec<ConnectionItem>) -> Result<T, String>,
+    {
+        let _guard = STORE_LOCK.lock().await;
+
+        let mut items = ConnectionStore::read_items().await?;
+        let result = apply(&mut items)?;
+        ConnectionStore::write_items(items).await?;
+
+        Ok(result)
+    }
+
+    pub async fn get_saved_brokers(tag: Option<String>) -> Result<Vec<ConnectionItem>, String> {
+        let _guard = STORE_LOCK.lock().await;
+        let items = ConnectionStore::read_items().await?;
+
+        Ok(items
+            .iter()
+            .filter(|item| match &tag {
+                Some(tag) => item.tags.iter().any(|item_tag| item_tag == tag),
+                None => true,
+            })
+            .map(ConnectionItem::public)
+            .collect())
+    }
+
+    async fn find(id: &str) -> Result<ConnectionItem, String> {
+        let _guard = STORE_LOCK.lock().await;
+        let items = ConnectionStore::read_items().await?;
+
+        items
+            .into_iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("Saved connection {} not found", id))
+    }
+
+    pub async fn resolve_secrets(
+        id: &str,
+        security: &mut KafkaSecurity,
+        schema_registry_security: &mut SchemaRegistrySecurity,
+    ) -> Result<(), String> {
+        let item = ConnectionStore::find(id).await?;
+
+        let mut secrets = item.secrets();
+        if item.has_credentials {
+            secrets = CredentialVault::get_secrets(id).await?;
+        }
+        security.apply_secrets(&secrets);
+        schema_registry_security.apply_secrets(&secrets);
+
+        Ok(())
+    }
+
+    pub async fn migrate_secrets() -> Result<(), String> {
+        let _guard = STORE_LOCK.lock().await;
+        let mut items = ConnectionStore::read_items().await?;
+
+        let mut migrated = 0;
+        for item in items.iter_mut() {
+            let secrets = item.secrets();
+            if secrets.is_empty() {
+                continue;
+            }
+
+            CredentialVault::store_secrets(&item.id, secrets).await?;
+            *item = item.without_secrets();
+            item.has_credentials = true;
+            migrated += 1;
+        }
+
+        if migrated > 0 {
+            println!("Moved credentials of {} connections to the vault", migrated);
+            ConnectionStore::write_items(items).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn store_broker(
+        host: &str,
+        name: &str,
+        schema_registry: &str,
+        schema_registry_security: &SchemaRegistrySecurity,
+        security: &KafkaSecurity,
+        properties: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let registry_secrets = schema_registry_security.secrets();
+        let secrets = ConnectionSecrets {
+            schema_registry_password: registry_secrets.schema_registry_password,
+            schema_registry_token: registry_secrets.schema_registry_token,
+            ..security.secrets()
+        };
+        // The connection already works at this point, so a locked vault only costs the saved
+        // credentials; previously stored ones stay in the vault untouched.
+        let save_secrets = secrets.is_empty() || CredentialVault::is_unlocked().await;
+        if !save_secrets {
+            println!(
+                "Credential vault is locked, saving {} without its credentials",
+                host
+            );
+        }
+
+        let id = ConnectionStore::modify(|items| {
+            match items.iter_mut().find(|item| {
+                &item.kafka_broker.to_string() == &host.to_string()
+                    && &item.schema_registry.to_string() == &schema_registry.to_string()
+            }) {
+                Some(item) => {
+                    item.security = security.without_secrets();
+                    item.schema_registry_security = schema_registry_security.without_secrets();
+                    item.properties = properties.clone();
+                    if save_secrets {
+                        item.has_credentials = !secrets.is_empty();
+                    }
+
+                    Ok(item.id.clone())
+                }
+                None => {
+                    let id = generate_item_id();
+                    items.push(ConnectionItem {
+                        id: id.clone(),
+                        kafka_broker: host.to_string(),
+                        schema_registry: schema_registry.to_string(),
+                        name: name.to_string(),
+                        tags: vec![],
+                        security: security.without_secrets(),
+                        schema_registry_security: schema_registry_security.without_secrets(),
+                        properties: properties.clone(),
+                        has_credentials: save_secrets && !secrets.is_empty(),
+                    });
+
+                    Ok(id)
+                }
+            }
+        })
+        .await?;
+
+        if !save_secrets {
+            return Ok(());
+        }
+
+        CredentialVault::store_secrets(&id, secrets).await
+    }
+
+    pub async fn update(mut connection: ConnectionItem) -> Result<ConnectionItem, String> {
+        let stored = ConnectionStore::find(&connection.id).await?;
+
+        let provided_secrets = match connection.secrets() {
+            secrets if secrets.is_empty() => stored.secrets(),
+            secrets => secrets,
+        };
+        let secrets = if provided_secrets.is_empty() && stored.has_credentials {
+            CredentialVault::get_secrets(&connection.id).await?
+        } else {
+            provided_secrets.clone()
+        };
+
+        let mut resolved = connection.clone();
+        resolved.apply_secrets(&secrets);
+        resolved.validate()?;
+
+        if !provided_secrets.is_empty() && !CredentialVault::is_unlocked().await {
+            return Err("Unlock the credential vault to save credentials".to_string());
+        }
+
+        connection = connection.without_secrets();
+        connection.has_credentials = !secrets.is_empty();
+        let updated = ConnectionStore::modify(|items| {
+            let item = find_item(items, &connection.id)?;
+            *item = ConnectionItem {
+                tags: normalize_tags(&connection.tags),
+                ..connection
+            };
+
+            Ok(item.public())
+        })
+        .await?;
+
+        if !provided_secrets.is_empty() {
+            CredentialVault::store_secrets(&updated.id, provided_secrets).await?;
+        }
+
+        Ok(updated)
+    }
+
+    pub async fn rename(id: &str, name: &str) -> Result<ConnectionItem, String> {
+        if name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        ConnectionStore::modify(|items| {
+            let item = find_item(items, id)?;
+            item.name = name.trim().to_string();
+
+            Ok(item.public())
+        })
+        .await
+    }
+
+    pub async fn delete(id: &str) -> Result<(), String> {
+        ConnectionStore::modify(|items| {
+            let position = find_position(items, id)?;
+            items.remove(position);
+
+            Ok(())
+        })
+        .await?;
+
+        CredentialVault::remove_secrets(id).await
+    }
+
+    pub async fn duplicate(id: &str) -> Result<ConnectionItem, String> {
+        let stored = ConnectionStore::find(id).await?;
+        let secrets = if stored.has_credentials {
+            CredentialVault::get_secrets(id).await?
+        } else {
+            ConnectionSecrets::default()
+        };
+
+        let copy = ConnectionStore::modify(|items| {
+            let position = find_position(items, id)?;
+            let mut copy = items[position].clone();
+            copy.id = generate_item_id();
+            copy.name = format!("{} (copy)", copy.name);
+            items.insert(position + 1, copy.clone());
+
+            Ok(copy.public())
+        })
+        .await?;
+
+        CredentialVault::store_secrets(&copy.id, secrets).await?;
+
+        Ok(copy)
+    }
+
+    pub async fn reorder(ids: Vec<String>) -> Result<Vec<ConnectionItem>, String> {
+        ConnectionStore::modify(|items| {
+            let mut reordered = Vec::with_capacity(items.len());
+
+            for id in ids.iter() {
+                let position = find_position(items, id)?;
+                reordered.push(items.remove(position));
+            }
+            reordered.append(items);
+            *items = reordered;
+
+            Ok(items.iter().map(ConnectionItem::public).collect())
+        })
+        .await
+    }
+
+    pub async fn export_profiles(
+        ids: &[String],
+        include_secrets: bool,
+    ) -> Result<Vec<ConnectionProfile>, String> {
+        let items = {
+            let _guard = STORE_LOCK.lock().await;
+            ConnectionStore::read_items().await?
+        };
+
+        let mut profiles = vec![];
+        for id in ids {
+            let item = items
+                .iter()
+                .find(|item| &item.id == id)
+                .ok_or_else(|| format!("Saved connection {} not found", id))?;
+
+            let mut exported = item.without_secrets();
+            if include_secrets {
+                exported.apply_secrets(&item.secrets());
+                if item.has_credentials {
+                    exported.apply_secrets(&CredentialVault::get_secrets(&item.id).await?);
+                }
+            }
+
+            profiles.push(ConnectionProfile {
+                name: item.name.clone(),
+                kafka_broker: item.kafka_broker.clone(),
+                schema_registry: item.schema_registry.clone(),
+                tags: item.tags.clone(),
+                security: exported.security,
+                schema_registry_security: exported.schema_registry_security,
+                properties: item.properties.clone(),
+            });
+        }
+
+        Ok(profiles)
+    }
+
+    pub async fn import_profiles(profiles: Vec<ConnectionProfile>) -> Result<ImportReport, String> {
+        let has_secrets = profiles.iter().any(|profile| {
+            !profile.security.secrets().is_empty()
+                || !profile.schema_registry_security.secrets().is_empty()
+        });
+        if has_secrets && !CredentialVault::is_unlocked().await {
+            return Err("Unlock the credential vault to import credentials".to_string());
+        }
+
+        let (imported, conflicts) = ConnectionStore::modify(|items| {
+            let mut imported = vec![];
+            let mut conflicts = vec![];
+
+            for profile in profiles {
+                let item = ConnectionItem {
+                    id: generate_item_id(),
+                    kafka_broker: profile.kafka_broker.trim().to_string(),
+                    schema_registry: profile.schema_registry.trim().to_string(),
+                    name: profile.name.trim().to_string(),
+                    tags: normalize_tags(&profile.tags),
+                    security: profile.security,
+                    schema_registry_security: profile.schema_registry_security,
+                    properties: profile.properties,
+                    has_credentials: false,
+                };
+
+                let reason = match item.validate_structure() {
+                    Err(e) => Some(e),
+                    Ok(_) => items.iter().find_map(|existing| {
+                        if existing.name.eq_ignore_ascii_case(&item.name) {
+                            Some(format!(
+                                "A connection named {} already exists",
+                                existing.name
+                            ))
+                        } else if existing.kafka_broker == item.kafka_broker
+                            && existing.schema_registry == item.schema_registry
+                        {
+                            Some(format!(
+                                "Connection {} already uses the same hosts",
+                                existing.name
+                            ))
+                        } else {
+                            None
+                        }
+                    }),
+                };
+
+                if let Some(reason) = reason {
+                    conflicts.push(ImportConflict {
+                        name: item.name,
+                        kafka_broker: item.kafka_broker,
+                        reason,
+                    });
+                    continue;
+                }
+
+                let secrets = item.secrets();
+                let stored = ConnectionItem {
+                    has_credentials: !secrets.is_empty(),
+                    ..item.without_secrets()
+                };
+                imported.push((stored.public(), secrets));
+                items.push(stored);
+            }
+
+            Ok((imported, conflicts))
+        })
+        .await?;
+
+        for (item, secrets) in imported.iter() {
+            CredentialVault::store_secrets(&item.id, secrets.clone()).await?;
+        }
+
+        Ok(ImportReport {
+            imported: imported.into_iter().map(|(item, _)| item).collect(),
+            conflicts,
+        })
+    }
+
+    pub async fn set_tags(id: &str, tags: Vec<String>) -> Result<ConnectionItem, String> {
+        ConnectionStore::modify(|items| {
+            let item = find_item(items, id)?;
+            item.tags = normalize_tags(&tags);
+
+            Ok(item.public())
+        })
+        .await
+    }
+}
+
+pub fn get_data_file_path(file_name: &str) -> PathBuf {
+    let local_data_dir = tauri::api::path::local_data_dir()
+        .unwrap_or(PathBuf::new())
+        .display()
+        .to_string();
+
+    PathBuf::from(local_data_dir)
+        .join("komprender")
+        .join(file_name)
+}
+
+pub async fn write_file_atomically(file_path: &Path, contents: &[u8]) -> Result<(), String> {
+    let mut tmp_name = file_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".komprender-tmp");
+    let tmp_path = file_path.with_file_name(tmp_name);
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let mut file = File::create(&tmp_path).await.map_err(|e| e.to_string())?;
+    file.write_all(contents).await.map_err(|e| e.to_string())?;
+    file.sync_all().await.map_err(|e| e.to_string())?;
+    drop(file);
+
+    fs::rename(&tmp_path, file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn read_items_from(file_path: &Path) -> Result<Vec<ConnectionItem>, String> {
+    if !file_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut file = File::open(file_path).await.map_err(|e| e.to_string())?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut file_contents: Connections =
+        serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+
+    let mut migrated = 0;
+    for item in file_contents.items.iter_mut() {
+        if item.id.is_empty() {
+            item.id = generate_item_id();
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        println!("Assigned ids to {} saved connections", migrated);
+        write_items_to(file_path, file_contents.items.clone()).await?;
+    }
+
+    Ok(file_contents.items)
+}
+
+async fn write_items_to(file_path: &Path, items: Vec<ConnectionItem>) -> Result<(), String> {
+    let serialized =
+        serde_json::to_string_pretty(&Connections { items }).map_err(|e| e.to_string())?;
+
+    write_file_atomically(file_path, serialized.as_bytes()).await
+}
+
+fn find_position(items: &[ConnectionItem], id: &str) -> Result<usize, String> {
+    items
+        .iter()
+        .position(|item| item.id == id)
+        .ok_or_else(|| format!("Saved connection {} not found", id))
+}
+
+fn find_item<'a>(
+    items: &'a mut [ConnectionItem],
+    id: &str,
+) -> Result<&'a mut ConnectionItem, String> {
+    let position = find_position(items, id)?;
+
+    Ok(&mut items[position])
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = vec![];
+
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    normalized
+}
+
+fn generate_item_id() -> String {
+    let rng = thread_rng();
+    rng.sample_iter(&Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect()
+}
+
+#[tauri::command]
+pub async fn update_saved_broker(connection: ConnectionItem) -> Result<ConnectionItem, String> {
+    ConnectionStore::update(connection).await
+}
+
+#[tauri::command]
+pub async fn rename_saved_broker(id: &str, name: &str) -> Result<ConnectionItem, String> {
+    ConnectionStore::rename(id, name).await
+}
+
+#[tauri::command]
+pub async fn delete_saved_broker(id: &str) -> Result<(), String> {
+    ConnectionStore::delete(id).await
+}
+
+#[tauri::command]
+pub async fn duplicate_saved_broker(id: &str) -> Result<ConnectionItem, String> {
+    ConnectionStore::duplicate(id).await
+}
+
+#[tauri::command]
+pub async fn reorder_saved_brokers(ids: Vec<String>) -> Result<Vec<ConnectionItem>, String> {
+    ConnectionStore::reorder(ids).await
+}
+
+#[tauri::command]
+pub async fn set_saved_broker_tags(id: &str, tags: Vec<String>) -> Result<ConnectionItem, String> {
+    ConnectionStore::set_tags(id, tags).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn persists_ids_of_legacy_connections() {
+        let directory = std::env::temp_dir().join(format!("komprender-{}", generate_item_id()));
+        let file_path = directory.join("brokers.json");
+        fs::create_dir_all(&directory).await.unwrap();
+        fs::write(
+            &file_path,
+            r#"{"items":[{"kafka_broker":"localhost:9092","schema_registry":"","name":"local"}]}"#,
+        )
+        .await
+        .unwrap();
+
+        let first = read_items_from(&file_path).await.unwrap();
+        let second = read_items_from(&file_path).await.unwrap();
+        fs::remove_dir_all(&directory).await.unwrap();
+
+        assert_eq!(first.len(), 1);
+        assert!(!first[0].id.is_empty());
+        assert_eq!(first[0].id, second[0].id);
+    }
+}
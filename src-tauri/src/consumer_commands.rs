@@ -1,60 +1,205 @@
 use crate::kafka_connection::{fetch_offsets, fulfill_tpl, KafkaConnection};
 use lazy_static::lazy_static;
-use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use rdkafka::admin::AdminOptions;
 use rdkafka::consumer::BaseConsumer;
+use rdkafka::error::KafkaError;
 use rdkafka::{
     consumer::{Consumer, StreamConsumer},
-    message::BorrowedMessage,
+    message::OwnedMessage,
     Message, Offset, TopicPartitionList,
 };
-use schema_registry_converter::async_impl::avro::AvroDecoder;
-use serde::Serialize;
-use serde_json::{self, Value as JsonValue};
-use tauri::{AppHandle, Manager};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Window;
 use tokio::sync::{mpsc, oneshot, oneshot::Sender, Mutex};
 use tokio::time::Duration;
 
-use crate::schema_registry::SchemaRegistry;
+use crate::deserializers::MessageDeserializers;
+use crate::message_filter::{CompiledFilter, MessageFilter};
+use crate::message_format::DataFormat;
+use crate::message_headers::{from_headers, MessageHeader};
+use crate::schema_registry::SchemaInfo;
+
+#[derive(Deserialize)]
+pub struct PartitionRange {
+    partition: i32,
+    start_offset: i64,
+    end_offset: Option<i64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ConsumeLimits {
+    max_messages: Option<usize>,
+    max_messages_per_partition: Option<usize>,
+    max_bytes: Option<usize>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+enum FinishReason {
+    LimitReached,
+    EndOfPartitions,
+    Cancelled,
+    Error,
+}
+
+#[derive(Serialize, Clone)]
+struct ConsumeFinished {
+    topic: String,
+    reason: FinishReason,
+    detail: Option<String>,
+    scanned: usize,
+    messages: usize,
+    bytes: usize,
+}
+
+#[derive(Serialize, Clone)]
+struct ConsumeProgress {
+    topic: String,
+    scanned: usize,
+    matched: usize,
+}
 
 #[derive(Serialize)]
 struct MessageResponse {
-    key: String,
+    key: JsonValue,
+    key_format: DataFormat,
+    key_content_type: Option<String>,
+    tombstone: bool,
     value: JsonValue,
+    value_format: DataFormat,
+    value_content_type: Option<String>,
+    key_schema: Option<SchemaInfo>,
+    key_error: Option<String>,
+    value_schema: Option<SchemaInfo>,
+    value_error: Option<String>,
     partition: i32,
     offset: i64,
+    headers: Vec<MessageHeader>,
+}
+
+const DEFAULT_MESSAGES_LIMIT: usize = 100;
+const WINDOW_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+const PROGRESS_INTERVAL: usize = 1000;
+const TECHNICAL_GROUP_PREFIX: &str = "__komprender-technical-consumer-group-";
+/// librdkafka refuses manual assignment without a group id. The group is never joined and no
+/// offsets are committed to it, so browsing leaves no group state behind.
+const BROWSER_GROUP_ID: &str = "__komprender-browser";
+
+#[derive(Serialize)]
+pub struct GroupCleanupFailure {
+    group: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+pub struct GroupCleanupReport {
+    deleted: Vec<String>,
+    failed: Vec<GroupCleanupFailure>,
 }
 
 lazy_static! {
-    static ref TX: Mutex<Vec<Sender<bool>>> = Mutex::new(vec![]);
+    static ref TX: Mutex<HashMap<String, Vec<Sender<bool>>>> = Mutex::new(HashMap::new());
 }
 
 #[tauri::command]
-pub async fn stop_consumers() {
-    println!("Stopping consumers");
+pub async fn stop_consumers(connection_id: String) {
+    println!("Stopping consumers of {}", connection_id);
     let mut signal_storage = TX.lock().await;
-    let signals = signal_storage.drain(..).collect::<Vec<_>>();
+    let signals = signal_storage.remove(&connection_id).unwrap_or_default();
     for tx in signals {
         let _ = tx.send(true);
     }
-    signal_storage.clear();
+}
+
+#[tauri::command]
+pub async fn delete_technical_consumer_groups(
+    connection_id: &str,
+) -> Result<GroupCleanupReport, String> {
+    let connection = KafkaConnection::get(connection_id).await?;
+    let groups: Vec<String> = connection
+        .consumer
+        .fetch_group_list(None, Duration::from_secs(10))
+        .map_err(|e| format!("Could not list consumer groups: {}", e))?
+        .groups()
+        .iter()
+        .map(|group| group.name().to_string())
+        .filter(|name| name.starts_with(TECHNICAL_GROUP_PREFIX))
+        .collect();
+
+    let mut report = GroupCleanupReport {
+        deleted: vec![],
+        failed: vec![],
+    };
+    if groups.is_empty() {
+        return Ok(report);
+    }
+
+    println!(
+        "Deleting {} leftover technical consumer groups",
+        groups.len()
+    );
+    let results = connection
+        .admin_client
+        .delete_groups(
+            &groups.iter().map(String::as_str).collect::<Vec<_>>(),
+            &AdminOptions::new(),
+        )
+        .await
+        .map_err(|e| format!("Could not delete consumer groups: {}", e))?;
+
+    for result in results {
+        match result {
+            Ok(group) => report.deleted.push(group),
+            Err((group, code)) => report.failed.push(GroupCleanupFailure {
+                group,
+                reason: code.to_string(),
+            }),
+        }
+    }
+
+    Ok(report)
 }
 
 #[tauri::command]
 pub async fn consume_messages(
-    app_handle: AppHandle,
+    window: Window,
+    connection_id: String,
     topic: String,
     mode: String,
+    from_timestamp: Option<i64>,
+    relative_duration_ms: Option<i64>,
+    partitions: Option<Vec<PartitionRange>>,
+    limits: Option<ConsumeLimits>,
+    filter: Option<MessageFilter>,
 ) -> Result<(), String> {
-    let avro_decoder = create_avro_decoder().await;
+    let limits = limits.unwrap_or_default();
+    let filter = filter.unwrap_or_default().compile()?;
+    let start_timestamp = resolve_start_timestamp(&mode, from_timestamp, relative_duration_ms)?;
+    let ranges = match mode.as_str() {
+        "range" => match partitions {
+            Some(ranges) if !ranges.is_empty() => Some(ranges),
+            _ => return Err("At least one partition is required for range mode".to_string()),
+        },
+        _ => None,
+    };
+    let deserializers = MessageDeserializers::new(&connection_id, &topic).await?;
 
     let (tx, rx) = oneshot::channel::<bool>();
     {
         let mut signal_storage = TX.lock().await;
-        signal_storage.push(tx);
+        signal_storage
+            .entry(connection_id.clone())
+            .or_default()
+            .push(tx);
     }
     let (tx_signal, mut rx_signal) = mpsc::channel::<()>(1);
 
-    let client_config = KafkaConnection::get_client_config()
+    let client_config = KafkaConnection::get_client_config(&connection_id)
         .await
         .map_err(|e| e.to_string())?;
     let base_consumer: BaseConsumer = client_config.create().map_err(|e| {
@@ -69,170 +214,583 @@ pub async fn consume_messages(
             }
         });
 
-        let mut messages_count = 0;
-        let group_id = generate_group_id();
-        let consumer = create_consumer(group_id).await?;
-
-        match consumer.subscribe(&[&*topic]) {
-            Ok(result) => result,
-            Err(err) => {
-                let err = format!("Error subscribing to topic: {}", err);
-                println!("Error subscribing to topic: {}", err);
-                return Err(err);
-            }
+        let max_messages = match (limits.max_messages, mode.as_str()) {
+            (Some(max_messages), _) => Some(max_messages),
+            (None, "beginning") | (None, "last") => Some(DEFAULT_MESSAGES_LIMIT),
+            (None, _) => None,
         };
+        let mut scanned_count = 0;
+        let mut messages_count = 0;
+        let mut bytes_count = 0;
 
-        println!("Subscribed to topic: {}", topic);
-        let mut seeked = false;
-        while let Ok(message) = tokio::select! {
-            message = consumer.recv() => message,
-            _ = rx_signal.recv() => {
-                println!("Cancellation signal received. Exiting...");
-                return Ok::<(), String>(());
-            },
-        } {
-            if !seeked {
-                println!("Starting seek");
-                seek(&consumer, &mode, &topic, &base_consumer).await?;
-                println!("Seeked");
-                seeked = true;
-                continue;
-            }
-            process_message(&message, &avro_decoder, &app_handle).await?;
-            messages_count += 1;
+        let outcome = async {
+            let timeout = async {
+                match limits.timeout_ms {
+                    Some(timeout_ms) => tokio::time::sleep(Duration::from_millis(timeout_ms)).await,
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            tokio::pin!(timeout);
+
+            let mut partition_counts: HashMap<i32, usize> = HashMap::new();
+            if ranges.is_none() && mode == "last" {
+                let last_messages = max_messages.unwrap_or(DEFAULT_MESSAGES_LIMIT);
+                let collected = tokio::select! {
+                    collected = collect_last_messages(
+                        &connection_id,
+                        &base_consumer,
+                        &topic,
+                        last_messages,
+                        &deserializers,
+                        &filter,
+                        &mut scanned_count,
+                    ) => collected?,
+                    _ = rx_signal.recv() => {
+                        println!("Cancellation signal received. Exiting...");
+                        return Ok((FinishReason::Cancelled, None));
+                    },
+                    _ = &mut timeout => {
+                        return Ok((FinishReason::LimitReached, Some("timeout".to_string())));
+                    },
+                };
 
-            if mode == "beginning" && messages_count >= 100 {
-                break;
+                // Limits keep the newest messages, which are then emitted oldest first.
+                let mut selected = vec![];
+                let mut stop = None;
+                for (message, response) in collected.into_iter().rev() {
+                    let partition_count = partition_counts.entry(message.partition()).or_default();
+                    if limits
+                        .max_messages_per_partition
+                        .map_or(false, |max_per_partition| {
+                            *partition_count >= max_per_partition
+                        })
+                    {
+                        continue;
+                    }
+
+                    *partition_count += 1;
+                    messages_count += 1;
+                    bytes_count += message_size(&message);
+                    selected.push(response);
+
+                    if limits
+                        .max_bytes
+                        .map_or(false, |max_bytes| bytes_count >= max_bytes)
+                    {
+                        stop = Some((FinishReason::LimitReached, Some("bytes".to_string())));
+                        break;
+                    }
+                }
+
+                for response in selected.iter().rev() {
+                    emit_message(&window, response);
+                }
+
+                return Ok(stop.unwrap_or(if messages_count >= last_messages {
+                    (FinishReason::LimitReached, Some("messages".to_string()))
+                } else {
+                    (FinishReason::EndOfPartitions, None)
+                }));
             }
+
+            // Every mode but "end" stops once all partitions are read up to their end.
+            let mut end_offsets = HashMap::new();
+            let mut pending_partitions = None;
+            let consumer = create_consumer(&connection_id, mode != "end").await?;
+            match &ranges {
+                Some(ranges) => {
+                    let (range_end_offsets, open_ended) =
+                        assign_ranges(&consumer, &base_consumer, &topic, ranges)?;
+                    if range_end_offsets.is_empty() && !open_ended {
+                        println!("Requested offset ranges of {} are empty", topic);
+                        return Ok((FinishReason::EndOfPartitions, None));
+                    }
+                    if !open_ended {
+                        pending_partitions =
+                            Some(range_end_offsets.keys().copied().collect::<HashSet<_>>());
+                    }
+                    end_offsets = range_end_offsets;
+                }
+                None => {
+                    let assignment =
+                        start_assignment(&base_consumer, &mode, &topic, start_timestamp)?;
+                    consumer.assign(&assignment).map_err(|e| {
+                        let err = format!("Error assigning partitions of topic: {}", e);
+                        println!("{}", err);
+                        err
+                    })?;
+                    println!("Assigned topic: {}", topic);
+
+                    if mode != "end" {
+                        pending_partitions = Some(
+                            assignment
+                                .elements()
+                                .iter()
+                                .map(|element| element.partition())
+                                .collect::<HashSet<_>>(),
+                        );
+                    }
+                }
+            };
+
+            let mut capped_partitions = HashSet::new();
+            let stop = loop {
+                let message = tokio::select! {
+                    message = consumer.recv() => match message {
+                        Ok(message) => message,
+                        Err(KafkaError::PartitionEOF(partition)) => {
+                            if finish_partition(&mut pending_partitions, partition) {
+                                println!("Reached end of all partitions of {}", topic);
+                                break (FinishReason::EndOfPartitions, None);
+                            }
+                            continue;
+                        }
+                        Err(e) => return Err(format!("Error receiving message: {}", e)),
+                    },
+                    _ = rx_signal.recv() => {
+                        println!("Cancellation signal received. Exiting...");
+                        break (FinishReason::Cancelled, None);
+                    },
+                    _ = &mut timeout => {
+                        break (FinishReason::LimitReached, Some("timeout".to_string()));
+                    },
+                };
+
+                // Transaction markers and compaction leave gaps, so a range ends at the first
+                // record at or past its end offset, or at the end of the partition.
+                let partition = message.partition();
+                let end_offset = end_offsets.get(&partition).copied();
+                let reached_end =
+                    end_offset.map_or(false, |end_offset| message.offset() >= end_offset);
+                let past_end = end_offset.map_or(false, |end_offset| message.offset() > end_offset);
+
+                if !past_end && !capped_partitions.contains(&partition) {
+                    scanned_count += 1;
+                    if scanned_count % PROGRESS_INTERVAL == 0 {
+                        emit_progress(&window, &topic, scanned_count, messages_count);
+                    }
+
+                    let matched = process_message(&message, &deserializers, &filter, &window).await;
+                    if matched {
+                        messages_count += 1;
+                        bytes_count += message_size(&message);
+
+                        if max_messages.map_or(false, |max_messages| messages_count >= max_messages)
+                        {
+                            break (FinishReason::LimitReached, Some("messages".to_string()));
+                        }
+
+                        if limits
+                            .max_bytes
+                            .map_or(false, |max_bytes| bytes_count >= max_bytes)
+                        {
+                            break (FinishReason::LimitReached, Some("bytes".to_string()));
+                        }
+                    }
+
+                    if let Some(max_per_partition) =
+                        limits.max_messages_per_partition.filter(|_| matched)
+                    {
+                        let partition_count = partition_counts.entry(partition).or_default();
+                        *partition_count += 1;
+                        if *partition_count >= max_per_partition {
+                            capped_partitions.insert(partition);
+                            let assigned = consumer.assignment().map_err(|e| e.to_string())?;
+                            if capped_partitions.len() >= assigned.count() {
+                                break (
+                                    FinishReason::LimitReached,
+                                    Some("messages per partition".to_string()),
+                                );
+                            }
+                            if finish_partition(&mut pending_partitions, partition) {
+                                println!("Reached end of all partitions of {}", topic);
+                                break (FinishReason::EndOfPartitions, None);
+                            }
+                        }
+                    }
+                }
+
+                if reached_end && finish_partition(&mut pending_partitions, partition) {
+                    println!("Reached end of all partitions of {}", topic);
+                    break (FinishReason::EndOfPartitions, None);
+                }
+            };
+
+            Ok::<(FinishReason, Option<String>), String>(stop)
         }
-        Ok::<(), String>(())
+        .await;
+
+        let (reason, detail) = match &outcome {
+            Ok((reason, detail)) => (reason.clone(), detail.clone()),
+            Err(e) => (FinishReason::Error, Some(e.clone())),
+        };
+        println!(
+            "Finished consuming {}: {} of {} scanned messages, {} bytes",
+            topic, messages_count, scanned_count, bytes_count
+        );
+        window
+            .emit(
+                "consume_finished",
+                ConsumeFinished {
+                    topic: topic.clone(),
+                    reason,
+                    detail,
+                    scanned: scanned_count,
+                    messages: messages_count,
+                    bytes: bytes_count,
+                },
+            )
+            .map_err(|e| eprintln!("Error emitting consume finished event: {:?}", e))
+            .ok();
+
+        outcome.map(|_| ())
     });
 
-    let _ = join_handle.await.map_err(|e| e.to_string())?;
-    Ok(())
+    join_handle.await.map_err(|e| e.to_string())?
 }
 
-async fn seek(
-    consumer: &StreamConsumer,
+fn start_assignment(
+    base_consumer: &BaseConsumer,
     mode: &str,
     topic: &str,
-    base_consumer: &BaseConsumer,
-) -> Result<(), String> {
-    let assignment = consumer.assignment().map_err(|e| e.to_string())?;
+    start_timestamp: Option<i64>,
+) -> Result<TopicPartitionList, String> {
+    let partitions = topic_partitions(base_consumer, topic)?;
+    let mut assignment = TopicPartitionList::new();
 
-    let offsets = match mode {
+    match mode {
         "end" => {
-            println!("Seeked to end of topic: {}", topic);
-            let partitions = assignment.elements();
-            for mut partition in partitions {
-                partition
-                    .set_offset(Offset::End)
-                    .map_err(|e| e.to_string())?;
-            }
-
-            assignment
-        }
-        "beginning" => {
-            println!("Starting to seek to beginning of topic: {}", topic);
-            let partitions = assignment.elements();
-            for mut partition in partitions {
-                partition
-                    .set_offset(Offset::Beginning)
-                    .map_err(|e| e.to_string())?;
+            println!("Assigning end of topic: {}", topic);
+            for partition in partitions {
+                fulfill_tpl(&mut assignment, topic, partition, Offset::End)?;
             }
-            println!("Seeked to beginning of topic: {}", topic);
-            assignment
         }
-        "last" => {
-            println!("Seeked to last 100 messages of topic: {}", topic);
-            let partitions = assignment.elements();
-            let partitions_count = partitions.len();
-            println!("Partitions count: {:?}", partitions_count);
-
-            let mut start_assignment = TopicPartitionList::new();
-            let mut end_assignment = TopicPartitionList::new();
+        "timestamp" | "relative" => {
+            let timestamp = start_timestamp.unwrap_or_default();
+            println!("Assigning timestamp {} of topic: {}", timestamp, topic);
 
+            let mut timestamp_assignment = TopicPartitionList::new();
             for partition in partitions {
                 fulfill_tpl(
-                    &mut start_assignment,
-                    topic,
-                    partition.partition(),
-                    Offset::Beginning,
-                )?;
-                fulfill_tpl(
-                    &mut end_assignment,
+                    &mut timestamp_assignment,
                     topic,
-                    partition.partition(),
-                    Offset::End,
-                )?;
-            }
-            println!("Partitions assigner, start fetching");
-            let end_offsets = fetch_offsets(&base_consumer, end_assignment)?;
-            println!("fetched end");
-            let end_offsets = end_offsets.elements_for_topic(&topic);
-            let start_offsets = fetch_offsets(&base_consumer, start_assignment)?;
-            println!("fetched start");
-            let start_offsets = start_offsets.elements_for_topic(&topic);
-
-            let mut result_tpl = TopicPartitionList::new();
-
-            for (index, partition) in end_offsets.iter().enumerate() {
-                let high = match partition.offset() {
-                    Offset::Offset(offset) => offset,
-                    _ => 0,
-                };
-                let low = match start_offsets[index].offset() {
-                    Offset::Offset(offset) => offset,
-                    _ => 0,
-                };
-                let result = (high as f64 - f64::from(100) / partitions_count as f64).ceil() as i64;
-                let final_result = std::cmp::max(result, low);
-                println!("Seeked to offset: {}", final_result);
-                fulfill_tpl(
-                    &mut result_tpl,
-                    topic,
-                    partition.partition(),
-                    Offset::Offset(final_result),
+                    partition,
+                    Offset::Offset(timestamp),
                 )?;
             }
 
-            result_tpl
+            assignment = fetch_offsets(&base_consumer, timestamp_assignment)?;
         }
         _ => {
-            let partitions = assignment.elements();
-            let mut offsets = vec![];
+            println!("Assigning beginning of topic: {}", topic);
             for partition in partitions {
-                offsets.push((partition.partition(), Offset::Beginning));
+                fulfill_tpl(&mut assignment, topic, partition, Offset::Beginning)?;
             }
+        }
+    }
+
+    Ok(assignment)
+}
 
-            assignment
+/// Reads the newest `count` messages of a topic that pass the filter, oldest first. Windows keep
+/// widening until enough messages match or the partitions are exhausted.
+async fn collect_last_messages(
+    connection_id: &str,
+    base_consumer: &BaseConsumer,
+    topic: &str,
+    count: usize,
+    deserializers: &MessageDeserializers,
+    filter: &CompiledFilter,
+    scanned_count: &mut usize,
+) -> Result<Vec<(OwnedMessage, MessageResponse)>, String> {
+    let mut low_watermarks = HashMap::new();
+    let mut windows = HashMap::new();
+    for partition in topic_partitions(base_consumer, topic)? {
+        let (low, high) = base_consumer
+            .fetch_watermarks(topic, partition, Duration::from_secs(5))
+            .map_err(|e| format!("Could not fetch offsets of partition {}: {}", partition, e))?;
+
+        if high > low {
+            low_watermarks.insert(partition, low);
+            windows.insert(partition, (std::cmp::max(high - count as i64, low), high));
         }
-    };
+    }
 
-    consumer
-        .seek_partitions(offsets, Duration::from_secs(5))
-        .map_err(|e| e.to_string())?;
+    let mut matched: HashMap<i32, Vec<(OwnedMessage, MessageResponse)>> = HashMap::new();
+    while !windows.is_empty() {
+        let mut collected = HashMap::new();
+        read_windows(connection_id, topic, &windows, &mut collected).await?;
+
+        for message in collected.into_values().flatten() {
+            *scanned_count += 1;
+            if let Some(response) = matching_response(&message, deserializers, filter).await {
+                matched
+                    .entry(message.partition())
+                    .or_default()
+                    .push((message, response));
+            }
+        }
+
+        let timestamps: HashMap<i32, Vec<i64>> = matched
+            .iter()
+            .map(|(partition, messages)| {
+                let timestamps = messages
+                    .iter()
+                    .map(|(message, _)| message_timestamp(message))
+                    .collect();
+                (*partition, timestamps)
+            })
+            .collect();
+        windows = widen_windows(&windows, &low_watermarks, &timestamps, count);
+    }
+
+    let mut messages: Vec<(OwnedMessage, MessageResponse)> =
+        matched.into_values().flatten().collect();
+    messages.sort_by_key(|(message, _)| {
+        (
+            message_timestamp(message),
+            message.partition(),
+            message.offset(),
+        )
+    });
+    let skipped = messages.len().saturating_sub(count);
+
+    Ok(messages.split_off(skipped))
+}
+
+/// Moves the window of every partition that may still hold one of the newest `count` matching
+/// messages further back, doubling its span each time.
+fn widen_windows(
+    windows: &HashMap<i32, (i64, i64)>,
+    low_watermarks: &HashMap<i32, i64>,
+    timestamps: &HashMap<i32, Vec<i64>>,
+    count: usize,
+) -> HashMap<i32, (i64, i64)> {
+    let mut newest: Vec<i64> = timestamps.values().flatten().copied().collect();
+    newest.sort_unstable_by(|a, b| b.cmp(a));
+    let threshold = newest.get(count.saturating_sub(1)).copied();
+
+    let mut next_windows = HashMap::new();
+    for (partition, (start, end)) in windows {
+        let low = low_watermarks[partition];
+        let partition_timestamps = timestamps.get(partition).map_or(&[][..], Vec::as_slice);
+        if *start <= low || partition_timestamps.len() >= count {
+            continue;
+        }
+
+        // Earlier offsets can only matter while this partition still reaches past the
+        // current cut-off; empty or compacted windows give no hint, so keep walking back.
+        let oldest = partition_timestamps.iter().min();
+        let may_contribute = match (threshold, oldest) {
+            (Some(threshold), Some(oldest)) => *oldest >= threshold,
+            _ => true,
+        };
+
+        if may_contribute {
+            let next_start = std::cmp::max(start - 2 * (end - start), low);
+            next_windows.insert(*partition, (next_start, *start));
+        }
+    }
+
+    next_windows
+}
+
+async fn read_windows(
+    connection_id: &str,
+    topic: &str,
+    windows: &HashMap<i32, (i64, i64)>,
+    collected: &mut HashMap<i32, Vec<OwnedMessage>>,
+) -> Result<(), String> {
+    let consumer = create_consumer(connection_id, true).await?;
+    let mut assignment = TopicPartitionList::new();
+    for (partition, (start, _)) in windows {
+        fulfill_tpl(&mut assignment, topic, *partition, Offset::Offset(*start))?;
+    }
+    consumer.assign(&assignment).map_err(|e| e.to_string())?;
+
+    let mut pending: HashSet<i32> = windows.keys().copied().collect();
+    while !pending.is_empty() {
+        let message = match tokio::time::timeout(WINDOW_IDLE_TIMEOUT, consumer.recv()).await {
+            Ok(Ok(message)) => message,
+            Ok(Err(KafkaError::PartitionEOF(partition))) => {
+                pending.remove(&partition);
+                continue;
+            }
+            Ok(Err(e)) => return Err(format!("Error receiving message: {}", e)),
+            Err(_) => {
+                println!(
+                    "No messages for {:?}, finishing partitions {:?}",
+                    WINDOW_IDLE_TIMEOUT, pending
+                );
+                break;
+            }
+        };
+
+        let partition = message.partition();
+        let (start, end) = match windows.get(&partition) {
+            Some(window) if pending.contains(&partition) => *window,
+            _ => continue,
+        };
+
+        if message.offset() >= start && message.offset() < end {
+            collected
+                .entry(partition)
+                .or_default()
+                .push(message.detach());
+        }
+
+        if message.offset() >= end - 1 {
+            pending.remove(&partition);
+        }
+    }
 
     Ok(())
 }
 
-fn generate_group_id() -> String {
-    let rng = thread_rng();
-    let random_string: String = rng
-        .sample_iter(&Alphanumeric)
-        .take(8)
-        .map(char::from)
-        .collect();
-    format!("__komprender-technical-consumer-group-{}", random_string)
+/// Marks a partition as done and returns whether it was the last one still being read.
+fn finish_partition(pending_partitions: &mut Option<HashSet<i32>>, partition: i32) -> bool {
+    match pending_partitions.as_mut() {
+        Some(pending) => pending.remove(&partition) && pending.is_empty(),
+        None => false,
+    }
+}
+
+fn message_timestamp(message: &OwnedMessage) -> i64 {
+    message.timestamp().to_millis().unwrap_or_default()
+}
+
+fn message_size<M: Message>(message: &M) -> usize {
+    message.key().map_or(0, <[u8]>::len) + message.payload().map_or(0, <[u8]>::len)
 }
 
-async fn create_consumer(group_id: String) -> Result<StreamConsumer, String> {
-    let mut client_config = KafkaConnection::get_client_config()
+fn topic_partitions(base_consumer: &BaseConsumer, topic: &str) -> Result<Vec<i32>, String> {
+    let metadata = base_consumer
+        .fetch_metadata(Some(topic), Duration::from_secs(10))
+        .map_err(|e| format!("Could not fetch metadata of topic {}: {}", topic, e))?;
+
+    let topic_metadata = metadata
+        .topics()
+        .iter()
+        .find(|topic_metadata| topic_metadata.name() == topic)
+        .ok_or_else(|| format!("Topic {} not found", topic))?;
+
+    if let Some(error) = topic_metadata.error() {
+        return Err(format!(
+            "Could not fetch metadata of topic {}: {:?}",
+            topic, error
+        ));
+    }
+
+    Ok(topic_metadata
+        .partitions()
+        .iter()
+        .map(|partition| partition.id())
+        .collect())
+}
+
+fn assign_ranges(
+    consumer: &StreamConsumer,
+    base_consumer: &BaseConsumer,
+    topic: &str,
+    ranges: &[PartitionRange],
+) -> Result<(HashMap<i32, i64>, bool), String> {
+    let mut assignment = TopicPartitionList::new();
+    let mut end_offsets = HashMap::new();
+    let mut open_ended = false;
+
+    for range in ranges {
+        if range.start_offset < 0 {
+            return Err(format!(
+                "Invalid start offset {} for partition {}",
+                range.start_offset, range.partition
+            ));
+        }
+
+        let (low, high) = base_consumer
+            .fetch_watermarks(topic, range.partition, Duration::from_secs(5))
+            .map_err(|e| {
+                format!(
+                    "Could not fetch offsets of partition {}: {}",
+                    range.partition, e
+                )
+            })?;
+        let start_offset = std::cmp::min(std::cmp::max(range.start_offset, low), high);
+
+        match range.end_offset {
+            Some(end_offset) if end_offset < range.start_offset => {
+                return Err(format!(
+                    "End offset {} is before start offset {} for partition {}",
+                    end_offset, range.start_offset, range.partition
+                ));
+            }
+            Some(end_offset) => {
+                let end_offset = std::cmp::min(end_offset, high - 1);
+                if start_offset > end_offset {
+                    continue;
+                }
+                end_offsets.insert(range.partition, end_offset);
+            }
+            None => open_ended = true,
+        }
+
+        fulfill_tpl(
+            &mut assignment,
+            topic,
+            range.partition,
+            Offset::Offset(start_offset),
+        )?;
+    }
+
+    consumer.assign(&assignment).map_err(|e| e.to_string())?;
+    println!(
+        "Assigned {} partitions of topic: {}",
+        assignment.count(),
+        topic
+    );
+
+    Ok((end_offsets, open_ended))
+}
+
+fn resolve_start_timestamp(
+    mode: &str,
+    from_timestamp: Option<i64>,
+    relative_duration_ms: Option<i64>,
+) -> Result<Option<i64>, String> {
+    match mode {
+        "timestamp" => match from_timestamp {
+            Some(timestamp) if timestamp >= 0 => Ok(Some(timestamp)),
+            Some(timestamp) => Err(format!("Invalid timestamp: {}", timestamp)),
+            None => Err("Timestamp is required for timestamp mode".to_string()),
+        },
+        "relative" => match relative_duration_ms {
+            Some(duration) if duration > 0 => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|e| e.to_string())?
+                    .as_millis() as i64;
+
+                Ok(Some(std::cmp::max(now - duration, 0)))
+            }
+            Some(duration) => Err(format!("Invalid relative duration: {}", duration)),
+            None => Err("Duration is required for relative mode".to_string()),
+        },
+        _ => Ok(None),
+    }
+}
+
+async fn create_consumer(
+    connection_id: &str,
+    partition_eof: bool,
+) -> Result<StreamConsumer, String> {
+    let mut client_config = KafkaConnection::get_client_config(connection_id)
         .await
         .map_err(|e| e.to_string())?;
 
     client_config
-        .set("group.id", group_id)
+        .set("group.id", BROWSER_GROUP_ID)
+        .set("enable.partition.eof", partition_eof.to_string())
+        .set("enable.auto.commit", "false")
+        .set("enable.auto.offset.store", "false")
         .set("auto.offset.reset", "earliest")
         .create()
         .map_err(|e| {
@@ -241,112 +799,116 @@ async fn create_consumer(group_id: String) -> Result<StreamConsumer, String> {
         })
 }
 
-async fn create_avro_decoder<'a>() -> Result<AvroDecoder<'a>, String> {
-    let sr_settings_guard = SchemaRegistry::get_settings().lock().await;
-    match &*sr_settings_guard {
-        Some(settings) => Ok(AvroDecoder::new(settings.clone())),
-        None => {
-            let err = "Error getting schema registry settings".to_string();
-            return Err(err);
+/// Decodes and emits the message when it passes the filter, returning whether it did.
+async fn process_message<M: Message>(
+    message: &M,
+    deserializers: &MessageDeserializers,
+    filter: &CompiledFilter,
+    window: &Window,
+) -> bool {
+    match matching_response(message, deserializers, filter).await {
+        Some(response) => {
+            emit_message(window, &response);
+            true
         }
+        None => false,
     }
 }
 
-async fn process_message<'a>(
-    message: &'a BorrowedMessage<'_>,
-    avro_decoder: &Result<AvroDecoder<'_>, String>,
-    app_handle: &AppHandle,
-) -> Result<(), String> {
-    match avro_decoder {
-        Ok(decoder) => {
-            if let Some(json) = decode_avro_to_json(message, &decoder).await {
-                emit_message(app_handle, &json);
-            } else if let Some(json) = decode_bytes_to_json(message)? {
-                emit_message(app_handle, &json);
-            }
-        }
-        Err(_) => {
-            if let Some(json) = decode_bytes_to_json(message)? {
-                emit_message(app_handle, &json);
-            }
-        }
+async fn matching_response<M: Message>(
+    message: &M,
+    deserializers: &MessageDeserializers,
+    filter: &CompiledFilter,
+) -> Option<MessageResponse> {
+    if !filter.matches_metadata(
+        message.partition(),
+        message.timestamp().to_millis(),
+        message.headers(),
+    ) {
+        return None;
     }
-    Ok(())
+
+    let key = deserializers.decode_key(message.key()).await;
+    let value = deserializers.decode_value(message.payload()).await;
+    if !filter.matches_data(&key.data.value, &value.data.value) {
+        return None;
+    }
+
+    Some(MessageResponse {
+        key: key.data.value,
+        key_format: key.data.format,
+        key_content_type: key.data.content_type,
+        key_schema: key.schema,
+        key_error: key.error,
+        tombstone: message.payload().is_none(),
+        value: value.data.value,
+        value_format: value.data.format,
+        value_content_type: value.data.content_type,
+        value_schema: value.schema,
+        value_error: value.error,
+        partition: message.partition(),
+        offset: message.offset(),
+        headers: from_headers(message.headers()),
+    })
 }
 
-fn emit_message(app_handle: &AppHandle, json: &MessageResponse) {
-    app_handle
-        .emit_all("message_received", json)
+fn emit_progress(window: &Window, topic: &str, scanned: usize, matched: usize) {
+    window
+        .emit(
+            "consume_progress",
+            ConsumeProgress {
+                topic: topic.to_string(),
+                scanned,
+                matched,
+            },
+        )
+        .map_err(|e| eprintln!("Error emitting consume progress event: {:?}", e))
+        .ok();
+}
+
+fn emit_message(window: &Window, json: &MessageResponse) {
+    window
+        .emit("message_received", json)
         .map_err(|e| eprintln!("Error emitting message event: {:?}", e))
         .ok();
 }
 
-fn get_message_data(message: &BorrowedMessage) -> (String, i32, i64) {
-    let key = message
-        .key()
-        .map(|k| std::str::from_utf8(k).unwrap_or_default())
-        .unwrap_or_default()
-        .to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let partition = message.partition();
-    let offset = message.offset();
+    #[test]
+    fn widens_only_partitions_that_may_hold_newer_messages() {
+        let windows = HashMap::from([(0, (6, 10)), (1, (6, 10)), (2, (8, 10)), (3, (0, 10))]);
+        let low_watermarks = HashMap::from([(0, 0), (1, 0), (2, 5), (3, 0)]);
+        let timestamps = HashMap::from([
+            (0, vec![500, 600, 700]),
+            (1, vec![100, 200, 300]),
+            (3, vec![50]),
+        ]);
 
-    (key, partition, offset)
-}
+        let next = widen_windows(&windows, &low_watermarks, &timestamps, 4);
 
-async fn decode_avro_to_json<'a>(
-    message: &'a BorrowedMessage<'_>,
-    avro_decoder: &'a AvroDecoder<'_>,
-) -> Option<MessageResponse> {
-    let (key, partition, offset) = get_message_data(message);
-
-    match avro_decoder.decode(message.payload()).await {
-        Ok(record) => {
-            let json_value = JsonValue::try_from(record.value);
-            match json_value {
-                Ok(json) => Some(MessageResponse {
-                    key,
-                    value: json,
-                    partition,
-                    offset,
-                }),
-                Err(e) => {
-                    eprintln!("Error converting Avro Value to JSON: {:?}", e);
-                    None
-                }
-            }
-        }
-        _ => None,
+        assert_eq!(next, HashMap::from([(0, (0, 6)), (2, (5, 8))]));
     }
-}
 
-fn decode_bytes_to_json(message: &BorrowedMessage) -> Result<Option<MessageResponse>, String> {
-    let (key, partition, offset) = get_message_data(message);
+    #[test]
+    fn keeps_walking_back_while_fewer_messages_than_requested() {
+        let windows = HashMap::from([(0, (90, 100)), (1, (95, 100))]);
+        let low_watermarks = HashMap::from([(0, 0), (1, 90)]);
+        let timestamps = HashMap::from([(0, vec![1, 2]), (1, vec![3])]);
 
-    let bytes = match message.payload() {
-        Some(b) => b,
-        None => return Err("Error getting message payload".to_string()),
-    };
-    let message_str = match std::str::from_utf8(bytes) {
-        Ok(str) => str,
-        Err(_) => return Err("Error decoding JSON message".to_string()),
-    };
+        let next = widen_windows(&windows, &low_watermarks, &timestamps, 10);
 
-    match serde_json::from_str::<JsonValue>(message_str) {
-        Ok(json) => Ok(Some(MessageResponse {
-            key,
-            value: json,
-            partition,
-            offset,
-        })),
-        Err(e) => {
-            println!("Error parsing JSON: {}", e);
-            Ok(Some(MessageResponse {
-                key,
-                value: message_str.into(),
-                partition,
-                offset,
-            }))
-        }
+        assert_eq!(next, HashMap::from([(0, (70, 90)), (1, (90, 95))]));
+    }
+
+    #[test]
+    fn stops_partitions_that_filled_the_count_on_their_own() {
+        let windows = HashMap::from([(0, (7, 10))]);
+        let low_watermarks = HashMap::from([(0, 0)]);
+        let timestamps = HashMap::from([(0, vec![1, 2, 3])]);
+
+        assert!(widen_windows(&windows, &low_watermarks, &timestamps, 3).is_empty());
     }
 }
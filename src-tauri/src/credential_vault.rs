@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use once_cell::sync::Lazy;
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::connection_store::{get_data_file_path, write_file_atomically, ConnectionStore};
+
+const VAULT_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+pub struct CredentialVault;
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ConnectionSecrets {
+    pub sasl_password: Option<String>,
+    pub ssl_key_password: Option<String>,
+    pub schema_registry_password: Option<String>,
+    pub schema_registry_token: Option<String>,
+}
+
+impl ConnectionSecrets {
+    pub fn is_empty(&self) -> bool {
+        *self == ConnectionSecrets::default()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u8,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize)]
+pub struct VaultStatus {
+    exists: bool,
+    unlocked: bool,
+}
+
+struct UnlockedVault {
+    key: [u8; 32],
+    salt: Vec<u8>,
+    secrets: HashMap<String, ConnectionSecrets>,
+}
+
+static VAULT: Lazy<Mutex<Option<UnlockedVault>>> = Lazy::new(|| Mutex::new(None));
+
+impl CredentialVault {
+    fn get_vault() -> &'static Mutex<Option<UnlockedVault>> {
+        &VAULT
+    }
+
+    pub async fn status() -> VaultStatus {
+        let vault = CredentialVault::get_vault().lock().await;
+
+        VaultStatus {
+            exists: get_data_file_path("vault.json").exists(),
+            unlocked: vault.is_some(),
+        }
+    }
+
+    pub async fn is_unlocked() -> bool {
+        CredentialVault::get_vault().lock().await.is_some()
+    }
+
+    pub async fn unlock(passphrase: &str) -> Result<(), String> {
+        let file_path = get_data_file_path("vault.json");
+
+        let unlocked = if file_path.exists() {
+            let contents = tokio::fs::read_to_string(&file_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            let vault_file: VaultFile =
+                serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+
+            decrypt_vault(&vault_file, passphrase)?
+        } else {
+            if passphrase.len() < MIN_PASSPHRASE_LENGTH {
+                return Err(format!(
+                    "Passphrase must be at least {} characters",
+                    MIN_PASSPHRASE_LENGTH
+                ));
+            }
+
+            let mut salt = vec![0u8; SALT_LENGTH];
+            thread_rng().fill_bytes(&mut salt);
+            let unlocked = UnlockedVault {
+                key: derive_key(passphrase, &salt)?,
+                salt,
+                secrets: HashMap::new(),
+            };
+            persist(&unlocked).await?;
+            println!("Created credential vault");
+
+            unlocked
+        };
+
+        {
+            let mut vault = CredentialVault::get_vault().lock().await;
+            *vault = Some(unlocked);
+        }
+
+        ConnectionStore::migrate_secrets().await
+    }
+
+    pub async fn lock() {
+        let mut vault = CredentialVault::get_vault().lock().await;
+        *vault = None;
+    }
+
+    pub async fn change_passphrase(current: &str, new: &str) -> Result<(), String> {
+        if new.len() < MIN_PASSPHRASE_LENGTH {
+            return Err(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LENGTH
+            ));
+        }
+
+        let mut vault = CredentialVault::get_vault().lock().await;
+        let unlocked = match &mut *vault {
+            Some(unlocked) => unlocked,
+            None => return Err("Credential vault is locked".to_string()),
+        };
+
+        if derive_key(current, &unlocked.salt)? != unlocked.key {
+            return Err("Wrong passphrase".to_string());
+        }
+
+        let mut salt = vec![0u8; SALT_LENGTH];
+        thread_rng().fill_bytes(&mut salt);
+        unlocked.key = derive_key(new, &salt)?;
+        unlocked.salt = salt;
+
+        persist(unlocked).await
+    }
+
+    pub async fn get_secrets(connection_id: &str) -> Result<ConnectionSecrets, String> {
+        let vault = CredentialVault::get_vault().lock().await;
+
+        match &*vault {
+            Some(unlocked) => Ok(unlocked
+                .secrets
+                .get(connection_id)
+                .cloned()
+                .unwrap_or_default()),
+            None => Err("Unlock the credential vault to use saved credentials".to_string()),
+        }
+    }
+
+    pub async fn store_secrets(
+        connection_id: &str,
+        secrets: ConnectionSecrets,
+    ) -> Result<(), String> {
+        let mut vault = CredentialVault::get_vault().lock().await;
+        let unlocked = match &mut *vault {
+            Some(unlocked) => unlocked,
+            None if secrets.is_empty() => return Ok(()),
+            None => return Err("Unlock the credential vault to save credentials".to_string()),
+        };
+
+        if secrets.is_empty() {
+            if unlocked.secrets.remove(connection_id).is_none() {
+                return Ok(());
+            }
+        } else {
+            unlocked.secrets.insert(connection_id.to_string(), secrets);
+        }
+
+        persist(unlocked).await
+    }
+
+    pub async fn remove_secrets(connection_id: &str) -> Result<(), String> {
+        let mut vault = CredentialVault::get_vault().lock().await;
+
+        if let Some(unlocked) = &mut *vault {
+            if unlocked.secrets.remove(connection_id).is_some() {
+                persist(unlocked).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Could not derive vault key: {}", e))?;
+
+    Ok(key)
+}
+
+fn decrypt_vault(vault_file: &VaultFile, passphrase: &str) -> Result<UnlockedVault, String> {
+    if vault_file.version != VAULT_VERSION {
+        return Err(format!(
+            "Unsupported credential vault version: {}",
+            vault_file.version
+        ));
+    }
+
+    let salt = STANDARD
+        .decode(&vault_file.salt)
+        .map_err(|e| e.to_string())?;
+    let nonce = STANDARD
+        .decode(&vault_file.nonce)
+        .map_err(|e| e.to_string())?;
+    let ciphertext = STANDARD
+        .decode(&vault_file.ciphertext)
+        .map_err(|e| e.to_string())?;
+
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Credential vault is corrupted".to_string());
+    }
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Wrong passphrase".to_string())?;
+    let secrets = serde_json::from_slice(&plaintext).map_err(|e| e.to_string())?;
+
+    Ok(UnlockedVault { key, salt, secrets })
+}
+
+async fn persist(unlocked: &UnlockedVault) -> Result<(), String> {
+    let plaintext = serde_json::to_vec(&unlocked.secrets).map_err(|e| e.to_string())?;
+
+    let mut nonce = [0u8; NONCE_LENGTH];
+    thread_rng().fill_bytes(&mut nonce);
+    let cipher = XChaCha20Poly1305::new_from_slice(&unlocked.key).map_err(|e| e.to_string())?;
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| "Could not encrypt credential vault".to_string())?;
+
+    let vault_file = VaultFile {
+        version: VAULT_VERSION,
+        salt: STANDARD.encode(&unlocked.salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    let serialized = serde_json::to_string_pretty(&vault_file).map_err(|e| e.to_string())?;
+
+    write_file_atomically(&get_data_file_path("vault.json"), serialized.as_bytes()).await
+}
+
+#[tauri::command]
+pub async fn vault_status() -> VaultStatus {
+    CredentialVault::status().await
+}
+
+#[tauri::command]
+pub async fn unlock_vault(passphrase: &str) -> Result<(), String> {
+    CredentialVault::unlock(passphrase).await
+}
+
+#[tauri::command]
+pub async fn lock_vault() {
+    CredentialVault::lock().await
+}
+
+#[tauri::command]
+pub async fn change_vault_passphrase(current: &str, new: &str) -> Result<(), String> {
+    CredentialVault::change_passphrase(current, new).await
+}
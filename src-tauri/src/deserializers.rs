@@ -0,0 +1,593 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use once_cell::sync::Lazy;
+use prost_reflect::FileDescriptor;
+use schema_registry_converter::async_impl::avro::AvroDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+
+use crate::connection_store::{get_data_file_path, write_file_atomically};
+use crate::kafka_connection::KafkaConnection;
+use crate::message_format::{
+    detect_content_type, has_confluent_framing, render_key, render_value, schema_id, to_hex,
+    DataFormat, RenderedData,
+};
+use crate::protobuf_format::{compile_schema, decode_to_json};
+use crate::schema_registry::{
+    pick_schema_version, RegisteredSchema, RegistryConnection, SchemaInfo, SchemaRegistry,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeserializerKind {
+    #[default]
+    Auto,
+    String,
+    Json,
+    Avro,
+    Protobuf,
+    JsonSchema,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+    Cbor,
+    Hex,
+    Base64,
+    Int,
+    Uuid,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct TopicDeserializers {
+    key: DeserializerKind,
+    value: DeserializerKind,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct DeserializerSettings {
+    topics: BTreeMap<String, TopicDeserializers>,
+}
+
+static SETTINGS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[async_trait]
+pub trait Deserializer: Send + Sync {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String>;
+}
+
+struct StringDeserializer;
+
+#[async_trait]
+impl Deserializer for StringDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let text = std::str::from_utf8(bytes).map_err(|e| format!("Not valid UTF-8: {}", e))?;
+
+        Ok(RenderedData {
+            value: text.into(),
+            format: DataFormat::Text,
+            content_type: Some("text/plain".to_string()),
+        })
+    }
+}
+
+struct JsonDeserializer;
+
+#[async_trait]
+impl Deserializer for JsonDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let json: JsonValue =
+            serde_json::from_slice(bytes).map_err(|e| format!("Not valid JSON: {}", e))?;
+
+        Ok(RenderedData {
+            value: json,
+            format: DataFormat::Json,
+            content_type: Some("application/json".to_string()),
+        })
+    }
+}
+
+struct JsonSchemaDeserializer;
+
+#[async_trait]
+impl Deserializer for JsonSchemaDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        decode_json_schema(bytes)
+    }
+}
+
+struct MessagePackDeserializer;
+
+#[async_trait]
+impl Deserializer for MessagePackDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let json: JsonValue =
+            rmp_serde::from_slice(bytes).map_err(|e| format!("Not valid MessagePack: {}", e))?;
+
+        Ok(RenderedData {
+            value: json,
+            format: DataFormat::MessagePack,
+            content_type: Some("application/msgpack".to_string()),
+        })
+    }
+}
+
+struct CborDeserializer;
+
+#[async_trait]
+impl Deserializer for CborDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let json: JsonValue =
+            ciborium::from_reader(bytes).map_err(|e| format!("Not valid CBOR: {}", e))?;
+
+        Ok(RenderedData {
+            value: json,
+            format: DataFormat::Cbor,
+            content_type: Some("application/cbor".to_string()),
+        })
+    }
+}
+
+struct HexDeserializer;
+
+#[async_trait]
+impl Deserializer for HexDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        Ok(RenderedData {
+            value: to_hex(bytes).into(),
+            format: DataFormat::Hex,
+            content_type: Some(detect_content_type(bytes).to_string()),
+        })
+    }
+}
+
+struct Base64Deserializer;
+
+#[async_trait]
+impl Deserializer for Base64Deserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        Ok(RenderedData {
+            value: STANDARD.encode(bytes).into(),
+            format: DataFormat::Base64,
+            content_type: Some(detect_content_type(bytes).to_string()),
+        })
+    }
+}
+
+/// Signed big-endian integers as written by the Kafka short, integer and long serializers.
+struct IntDeserializer;
+
+#[async_trait]
+impl Deserializer for IntDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let value = match bytes.len() {
+            1 => i64::from(bytes[0] as i8),
+            2 => i64::from(i16::from_be_bytes([bytes[0], bytes[1]])),
+            4 => i64::from(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+            8 => i64::from_be_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]),
+            length => return Err(format!("Not a big-endian integer: {} bytes", length)),
+        };
+
+        Ok(RenderedData {
+            value: value.into(),
+            format: DataFormat::Integer,
+            content_type: None,
+        })
+    }
+}
+
+/// UUIDs written as their 16 raw bytes, most significant first.
+struct UuidDeserializer;
+
+#[async_trait]
+impl Deserializer for UuidDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        if bytes.len() != 16 {
+            return Err(format!("Not a binary UUID: {} bytes", bytes.len()));
+        }
+
+        let hex = to_hex(bytes);
+        Ok(RenderedData {
+            value: format!(
+                "{}-{}-{}-{}-{}",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..32]
+            )
+            .into(),
+            format: DataFormat::Uuid,
+            content_type: None,
+        })
+    }
+}
+
+struct AvroDeserializer {
+    registry: Arc<RegistryDecoder>,
+}
+
+#[async_trait]
+impl Deserializer for AvroDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        self.registry.decode_avro(bytes).await
+    }
+}
+
+struct ProtobufDeserializer {
+    registry: Arc<RegistryDecoder>,
+}
+
+#[async_trait]
+impl Deserializer for ProtobufDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let id = schema_id(bytes).ok_or_else(|| "Missing Schema Registry framing".to_string())?;
+
+        self.registry.decode_protobuf(id, bytes).await
+    }
+}
+
+/// Decodes Schema Registry framed records by their registered schema type and falls back to
+/// text, JSON or binary rendering for everything else.
+struct AutoDeserializer {
+    is_key: bool,
+    registry: Option<Arc<RegistryDecoder>>,
+}
+
+#[async_trait]
+impl Deserializer for AutoDeserializer {
+    async fn deserialize(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        if has_confluent_framing(bytes) {
+            let decoded = match &self.registry {
+                Some(registry) => registry.decode_framed(bytes).await,
+                None => decode_json_schema(bytes).ok(),
+            };
+
+            if let Some(decoded) = decoded {
+                return Ok(decoded);
+            }
+        }
+
+        if self.is_key {
+            Ok(render_key(Some(bytes)))
+        } else {
+            Ok(render_value(bytes))
+        }
+    }
+}
+
+/// Schema Registry lookups shared by the registry backed deserializers of one consumer.
+pub struct RegistryDecoder {
+    avro_decoder: AvroDecoder<'static>,
+    registry: RegistryConnection,
+    registered_schemas: Mutex<HashMap<u32, Option<RegisteredSchema>>>,
+    protobuf_files: Mutex<HashMap<u32, Result<FileDescriptor, String>>>,
+    schema_versions: Mutex<HashMap<u32, Vec<SchemaInfo>>>,
+}
+
+impl RegistryDecoder {
+    fn new(registry: RegistryConnection) -> RegistryDecoder {
+        RegistryDecoder {
+            avro_decoder: AvroDecoder::new(registry.settings.clone()),
+            registry,
+            registered_schemas: Mutex::new(HashMap::new()),
+            protobuf_files: Mutex::new(HashMap::new()),
+            schema_versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn registered_schema(&self, id: u32) -> Option<RegisteredSchema> {
+        let mut registered_schemas = self.registered_schemas.lock().await;
+
+        if !registered_schemas.contains_key(&id) {
+            let registered = self
+                .registry
+                .registered_schema(id)
+                .await
+                .map_err(|e| eprintln!("Error fetching schema {}: {}", id, e))
+                .ok();
+            registered_schemas.insert(id, registered);
+        }
+
+        registered_schemas[&id].clone()
+    }
+
+    async fn decode_framed(&self, bytes: &[u8]) -> Option<RenderedData> {
+        let id = schema_id(bytes)?;
+
+        let decoded = match self.registered_schema(id).await {
+            Some(registered) if registered.schema_type() == "PROTOBUF" => {
+                self.decode_protobuf(id, bytes).await
+            }
+            Some(registered) if registered.schema_type() == "JSON" => decode_json_schema(bytes),
+            Some(_) => self.decode_avro(bytes).await,
+            None => match self.decode_avro(bytes).await {
+                Ok(decoded) => Ok(decoded),
+                Err(_) => decode_json_schema(bytes),
+            },
+        };
+
+        decoded.map_err(|e| eprintln!("{}", e)).ok()
+    }
+
+    async fn decode_avro(&self, bytes: &[u8]) -> Result<RenderedData, String> {
+        let record = self
+            .avro_decoder
+            .decode(Some(bytes))
+            .await
+            .map_err(|e| format!("Error decoding Avro message: {}", e))?;
+        let json = JsonValue::try_from(record.value)
+            .map_err(|e| format!("Error converting Avro Value to JSON: {:?}", e))?;
+
+        Ok(RenderedData {
+            value: json,
+            format: DataFormat::Avro,
+            content_type: Some("application/vnd.confluent.avro".to_string()),
+        })
+    }
+
+    async fn decode_protobuf(&self, id: u32, bytes: &[u8]) -> Result<RenderedData, String> {
+        let file = {
+            let mut protobuf_files = self.protobuf_files.lock().await;
+
+            if !protobuf_files.contains_key(&id) {
+                let file = match self.registered_schema(id).await {
+                    Some(registered) if registered.schema_type() == "PROTOBUF" => {
+                        match self
+                            .registry
+                            .resolve_references(&registered.references)
+                            .await
+                        {
+                            Ok(references) => compile_schema(&registered.schema, references),
+                            Err(e) => Err(e),
+                        }
+                    }
+                    Some(registered) => Err(format!(
+                        "Schema {} is a {} schema, not a Protobuf schema",
+                        id,
+                        registered.schema_type()
+                    )),
+                    None => Err(format!("Schema {} not found", id)),
+                };
+                protobuf_files.insert(id, file);
+            }
+
+            protobuf_files[&id].clone()
+        };
+
+        let json = decode_to_json(&file?, &bytes[5..])
+            .map_err(|e| format!("Error decoding Protobuf message: {}", e))?;
+
+        Ok(RenderedData {
+            value: json,
+            format: DataFormat::Protobuf,
+            content_type: Some("application/vnd.confluent.protobuf".to_string()),
+        })
+    }
+
+    async fn schema_info(&self, id: u32, topic: &str, is_key: bool) -> SchemaInfo {
+        let mut schema_versions = self.schema_versions.lock().await;
+
+        if !schema_versions.contains_key(&id) {
+            let versions = self.registry.schema_versions(id).await.unwrap_or_else(|e| {
+                eprintln!("Error resolving subject of schema {}: {}", id, e);
+                vec![]
+            });
+            schema_versions.insert(id, versions);
+        }
+
+        pick_schema_version(&schema_versions[&id], topic, is_key).unwrap_or(SchemaInfo {
+            id,
+            subject: None,
+            version: None,
+        })
+    }
+}
+
+pub struct DecodedData {
+    pub data: RenderedData,
+    pub schema: Option<SchemaInfo>,
+    pub error: Option<String>,
+}
+
+/// The key and value deserializers a consumer applies to every record of a topic.
+pub struct MessageDeserializers {
+    topic: String,
+    key: Box<dyn Deserializer>,
+    value: Box<dyn Deserializer>,
+    registry: Option<Arc<RegistryDecoder>>,
+}
+
+impl MessageDeserializers {
+    pub async fn new(connection_id: &str, topic: &str) -> Result<MessageDeserializers, String> {
+        let settings = load_topic_deserializers(connection_id, topic).await?;
+        let registry = SchemaRegistry::get_connection(connection_id)
+            .await
+            .ok()
+            .map(|registry| Arc::new(RegistryDecoder::new(registry)));
+
+        Ok(MessageDeserializers {
+            topic: topic.to_string(),
+            key: create_deserializer(settings.key, true, &registry)?,
+            value: create_deserializer(settings.value, false, &registry)?,
+            registry,
+        })
+    }
+
+    pub async fn decode_key(&self, bytes: Option<&[u8]>) -> DecodedData {
+        match bytes {
+            Some(bytes) => self.decode(self.key.as_ref(), bytes, true).await,
+            None => DecodedData {
+                data: RenderedData::null(),
+                schema: None,
+                error: None,
+            },
+        }
+    }
+
+    pub async fn decode_value(&self, bytes: Option<&[u8]>) -> DecodedData {
+        match bytes {
+            Some(bytes) => self.decode(self.value.as_ref(), bytes, false).await,
+            None => DecodedData {
+                data: RenderedData::null(),
+                schema: None,
+                error: None,
+            },
+        }
+    }
+
+    async fn decode(
+        &self,
+        deserializer: &dyn Deserializer,
+        bytes: &[u8],
+        is_key: bool,
+    ) -> DecodedData {
+        let (data, error) = match deserializer.deserialize(bytes).await {
+            Ok(data) => (data, None),
+            Err(e) if is_key => (render_key(Some(bytes)), Some(e)),
+            Err(e) => (render_value(bytes), Some(e)),
+        };
+
+        let schema = match (data.format, schema_id(bytes)) {
+            (DataFormat::Avro | DataFormat::Protobuf | DataFormat::JsonSchema, Some(id)) => {
+                Some(match &self.registry {
+                    Some(registry) => registry.schema_info(id, &self.topic, is_key).await,
+                    None => SchemaInfo {
+                        id,
+                        subject: None,
+                        version: None,
+                    },
+                })
+            }
+            _ => None,
+        };
+
+        DecodedData {
+            data,
+            schema,
+            error,
+        }
+    }
+}
+
+fn create_deserializer(
+    kind: DeserializerKind,
+    is_key: bool,
+    registry: &Option<Arc<RegistryDecoder>>,
+) -> Result<Box<dyn Deserializer>, String> {
+    let registry_required =
+        || "Schema Registry must be connected for this deserializer".to_string();
+
+    Ok(match kind {
+        DeserializerKind::Auto => Box::new(AutoDeserializer {
+            is_key,
+            registry: registry.clone(),
+        }),
+        DeserializerKind::String => Box::new(StringDeserializer),
+        DeserializerKind::Json => Box::new(JsonDeserializer),
+        DeserializerKind::Avro => Box::new(AvroDeserializer {
+            registry: registry.clone().ok_or_else(registry_required)?,
+        }),
+        DeserializerKind::Protobuf => Box::new(ProtobufDeserializer {
+            registry: registry.clone().ok_or_else(registry_required)?,
+        }),
+        DeserializerKind::JsonSchema => Box::new(JsonSchemaDeserializer),
+        DeserializerKind::MessagePack => Box::new(MessagePackDeserializer),
+        DeserializerKind::Cbor => Box::new(CborDeserializer),
+        DeserializerKind::Hex => Box::new(HexDeserializer),
+        DeserializerKind::Base64 => Box::new(Base64Deserializer),
+        DeserializerKind::Int => Box::new(IntDeserializer),
+        DeserializerKind::Uuid => Box::new(UuidDeserializer),
+    })
+}
+
+fn decode_json_schema(bytes: &[u8]) -> Result<RenderedData, String> {
+    if !has_confluent_framing(bytes) {
+        return Err("Missing Schema Registry framing".to_string());
+    }
+
+    let json: JsonValue = serde_json::from_slice(&bytes[5..])
+        .map_err(|e| format!("Error parsing JSON Schema framed message: {}", e))?;
+
+    Ok(RenderedData {
+        value: json,
+        format: DataFormat::JsonSchema,
+        content_type: Some("application/vnd.confluent.jsonschema".to_string()),
+    })
+}
+
+/// Settings are kept per cluster, identified by its bootstrap servers, since connection ids
+/// only live as long as the connection.
+async fn settings_key(connection_id: &str, topic: &str) -> Result<String, String> {
+    let client_config = KafkaConnection::get_client_config(connection_id).await?;
+    let broker = client_config.get("bootstrap.servers").unwrap_or_default();
+
+    Ok(format!("{}/{}", broker, topic))
+}
+
+async fn read_settings() -> Result<DeserializerSettings, String> {
+    let file_path = get_data_file_path("deserializers.json");
+
+    if !file_path.exists() {
+        return Ok(DeserializerSettings::default());
+    }
+
+    let contents = tokio::fs::read_to_string(&file_path)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+async fn load_topic_deserializers(
+    connection_id: &str,
+    topic: &str,
+) -> Result<TopicDeserializers, String> {
+    let key = settings_key(connection_id, topic).await?;
+    let _guard = SETTINGS_LOCK.lock().await;
+
+    Ok(read_settings()
+        .await?
+        .topics
+        .get(&key)
+        .copied()
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn fetch_topic_deserializers(
+    connection_id: &str,
+    topic: &str,
+) -> Result<TopicDeserializers, String> {
+    load_topic_deserializers(connection_id, topic).await
+}
+
+#[tauri::command]
+pub async fn save_topic_deserializers(
+    connection_id: &str,
+    topic: &str,
+    deserializers: TopicDeserializers,
+) -> Result<(), String> {
+    let key = settings_key(connection_id, topic).await?;
+    let _guard = SETTINGS_LOCK.lock().await;
+
+    let mut settings = read_settings().await?;
+    if deserializers.key == DeserializerKind::Auto && deserializers.value == DeserializerKind::Auto
+    {
+        settings.topics.remove(&key);
+    } else {
+        settings.topics.insert(key, deserializers);
+    }
+
+    let serialized = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    write_file_atomically(
+        &get_data_file_path("deserializers.json"),
+        serialized.as_bytes(),
+    )
+    .await
+}
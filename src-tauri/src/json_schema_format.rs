@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use jsonschema::JSONSchema;
+use serde_json::Value as JsonValue;
+
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// Validates a payload against a registry JSON Schema, resolving `$ref`s to referenced schemas
+/// by the name they were registered under.
+pub fn validate(
+    schema: &str,
+    references: BTreeMap<String, String>,
+    payload: &JsonValue,
+) -> Result<(), String> {
+    let schema: JsonValue =
+        serde_json::from_str(schema).map_err(|e| format!("Invalid JSON Schema: {}", e))?;
+
+    let mut options = JSONSchema::options();
+    for (name, source) in references {
+        let document = serde_json::from_str(&source)
+            .map_err(|e| format!("Invalid referenced JSON Schema {}: {}", name, e))?;
+        options.with_document(name, document);
+    }
+
+    let compiled = options
+        .compile(&schema)
+        .map_err(|e| format!("Invalid JSON Schema: {}", e))?;
+
+    let result = compiled.validate(payload);
+    if let Err(errors) = result {
+        let errors: Vec<String> = errors
+            .take(MAX_REPORTED_ERRORS)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                if path.is_empty() {
+                    error.to_string()
+                } else {
+                    format!("{}: {}", path, error)
+                }
+            })
+            .collect();
+
+        return Err(format!(
+            "Payload does not match JSON Schema: {}",
+            errors.join("; ")
+        ));
+    }
+
+    Ok(())
+}
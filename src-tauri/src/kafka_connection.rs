@@ -1,273 +1,465 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 
 use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use rdkafka::admin::AdminClient;
 use rdkafka::client::DefaultClientContext;
 use rdkafka::config::FromClientConfig;
-use rdkafka::consumer::{BaseConsumer, Consumer, StreamConsumer};
+use rdkafka::consumer::{BaseConsumer, Consumer, ConsumerContext};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
 use rdkafka::producer::FutureProducer;
-use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use rdkafka::{ClientConfig, ClientContext, Offset, TopicPartitionList};
 use serde::{Deserialize, Serialize};
-use tokio::fs::{self, File};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::Mutex;
 
+use crate::client_properties::validate_properties;
+use crate::connection_store::ConnectionStore;
+use crate::credential_vault::ConnectionSecrets;
+use crate::schema_registry::{RegistryConnection, SchemaRegistrySecurity};
+
 pub struct KafkaConnection;
 
-#[derive(Serialize, Deserialize)]
-struct Connections {
-    items: Vec<ConnectionItem>,
-}
-#[derive(Serialize, Deserialize)]
-pub struct ConnectionItem {
-    kafka_broker: String,
-    schema_registry: String,
-    name: String,
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct KafkaSecurity {
+    security_protocol: Option<String>,
+    sasl_mechanism: Option<String>,
+    sasl_username: Option<String>,
+    sasl_password: Option<String>,
+    ssl_ca_location: Option<String>,
+    ssl_certificate_location: Option<String>,
+    ssl_key_location: Option<String>,
+    ssl_key_password: Option<String>,
+    ssl_disable_hostname_verification: bool,
 }
 
-pub static KAFKA_CONSUMER: Lazy<Mutex<Option<BaseConsumer>>> = Lazy::new(|| Mutex::new(None));
-pub static KAFKA_STREAM_CONSUMER: Lazy<Mutex<Option<StreamConsumer>>> =
-    Lazy::new(|| Mutex::new(None));
-pub static KAFKA_PRODUCER: Lazy<Mutex<Option<FutureProducer>>> = Lazy::new(|| Mutex::new(None));
-pub static KAFKA_ADMIN_CLIENT: Lazy<Mutex<Option<AdminClient<DefaultClientContext>>>> =
-    Lazy::new(|| Mutex::new(None));
-pub static BROKER: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
-
-impl KafkaConnection {
-    pub fn get_consumer_instance() -> &'static Mutex<Option<BaseConsumer>> {
-        &KAFKA_CONSUMER
+const SECURITY_PROTOCOLS: [&str; 4] = ["PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL"];
+const SASL_MECHANISMS: [&str; 3] = ["PLAIN", "SCRAM-SHA-256", "SCRAM-SHA-512"];
+const SECURITY_PROPERTIES: [&str; 10] = [
+    "security.protocol",
+    "sasl.mechanism",
+    "sasl.mechanisms",
+    "sasl.username",
+    "sasl.password",
+    "ssl.ca.location",
+    "ssl.certificate.location",
+    "ssl.key.location",
+    "ssl.key.password",
+    "ssl.endpoint.identification.algorithm",
+];
+
+impl KafkaSecurity {
+    fn protocol(&self) -> Option<String> {
+        self.security_protocol
+            .as_ref()
+            .filter(|protocol| !protocol.is_empty())
+            .map(|protocol| protocol.to_ascii_uppercase())
     }
 
-    pub fn get_stream_consumer_instance() -> &'static Mutex<Option<StreamConsumer>> {
-        &KAFKA_STREAM_CONSUMER
+    fn uses_sasl(&self) -> bool {
+        matches!(
+            self.protocol().as_deref(),
+            Some("SASL_PLAINTEXT") | Some("SASL_SSL")
+        )
     }
 
-    pub fn get_producer_instance() -> &'static Mutex<Option<FutureProducer>> {
-        &KAFKA_PRODUCER
+    pub fn is_security_property(key: &str) -> bool {
+        SECURITY_PROPERTIES.contains(&key)
     }
 
-    pub fn get_admin_client_instance() -> &'static Mutex<Option<AdminClient<DefaultClientContext>>>
-    {
-        &KAFKA_ADMIN_CLIENT
+    pub fn take_from_properties(properties: &mut BTreeMap<String, String>) -> KafkaSecurity {
+        let mut take = |key: &str| properties.remove(key).filter(|value| !value.is_empty());
+
+        let security_protocol = take("security.protocol");
+        let sasl_mechanism = take("sasl.mechanism").or_else(|| take("sasl.mechanisms"));
+
+        KafkaSecurity {
+            security_protocol,
+            sasl_mechanism,
+            sasl_username: take("sasl.username"),
+            sasl_password: take("sasl.password"),
+            ssl_ca_location: take("ssl.ca.location"),
+            ssl_certificate_location: take("ssl.certificate.location"),
+            ssl_key_location: take("ssl.key.location"),
+            ssl_key_password: take("ssl.key.password"),
+            ssl_disable_hostname_verification: take("ssl.endpoint.identification.algorithm")
+                .map_or(false, |algorithm| algorithm.eq_ignore_ascii_case("none")),
+        }
     }
 
-    fn get_broker() -> &'static Mutex<Option<String>> {
-        &BROKER
+    pub fn secrets(&self) -> ConnectionSecrets {
+        ConnectionSecrets {
+            sasl_password: non_empty(&self.sasl_password),
+            ssl_key_password: non_empty(&self.ssl_key_password),
+            ..ConnectionSecrets::default()
+        }
     }
 
-    pub async fn get_client_config<'a>() -> Result<ClientConfig, String> {
-        let mut config = ClientConfig::new();
-        let broker = KafkaConnection::get_broker().lock().await;
-
-        let broker = match &*broker {
-            Some(broker) => broker.clone(),
-            None => {
-                return Err("Broker not set".to_string());
-            }
-        };
+    pub fn without_secrets(&self) -> KafkaSecurity {
+        KafkaSecurity {
+            sasl_password: None,
+            ssl_key_password: None,
+            ..self.clone()
+        }
+    }
 
-        config.set("bootstrap.servers", broker);
+    pub fn apply_secrets(&mut self, secrets: &ConnectionSecrets) {
+        if non_empty(&self.sasl_password).is_none() {
+            self.sasl_password = secrets.sasl_password.clone();
+        }
 
-        Ok(config)
+        if non_empty(&self.ssl_key_password).is_none() {
+            self.ssl_key_password = secrets.ssl_key_password.clone();
+        }
     }
 
-    pub async fn disconnect() -> Result<(), String> {
-        let mut kafka_consumer_guard = KafkaConnection::get_consumer_instance().lock().await;
-        *kafka_consumer_guard = None;
-
-        let mut kafka_admin_client_guard =
-            KafkaConnection::get_admin_client_instance().lock().await;
-        *kafka_admin_client_guard = None;
+    fn uses_ssl(&self) -> bool {
+        matches!(self.protocol().as_deref(), Some("SSL") | Some("SASL_SSL"))
+    }
 
-        let mut kafka_producer_guard = KafkaConnection::get_producer_instance().lock().await;
-        *kafka_producer_guard = None;
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_structure()?;
 
-        let mut kafka_stream_consumer_guard =
-            KafkaConnection::get_stream_consumer_instance().lock().await;
-        *kafka_stream_consumer_guard = None;
+        if self.uses_ssl() {
+            self.validate_ssl_files()?;
+        }
 
-        let mut kafka_broker = KafkaConnection::get_broker().lock().await;
-        *kafka_broker = None;
+        if self.uses_sasl() && self.sasl_password.clone().unwrap_or_default().is_empty() {
+            return Err("SASL password cannot be empty".to_string());
+        }
 
         Ok(())
     }
 
-    pub async fn connect(host: &str, name: &str, schema_registry: &str) -> Result<bool, String> {
-        if host.is_empty() {
-            return Err("Broker cannot be empty".to_string());
+    pub fn validate_structure(&self) -> Result<(), String> {
+        if let Some(protocol) = self.protocol() {
+            if !SECURITY_PROTOCOLS.contains(&protocol.as_str()) {
+                return Err(format!(
+                    "Unknown security protocol: {}. Expected one of {}",
+                    protocol,
+                    SECURITY_PROTOCOLS.join(", ")
+                ));
+            }
         }
 
-        if name.is_empty() {
-            return Err("Name cannot be empty".to_string());
+        if self.uses_ssl() {
+            match (
+                non_empty(&self.ssl_certificate_location),
+                non_empty(&self.ssl_key_location),
+            ) {
+                (Some(_), None) => {
+                    return Err("Client key is required with client certificate".to_string())
+                }
+                (None, Some(_)) => {
+                    return Err("Client certificate is required with client key".to_string())
+                }
+                _ => {}
+            }
         }
 
-        let broker = if !host.contains(':') {
-            format!("{}:9092", host)
-        } else {
-            host.to_string()
-        };
-        println!("Connecting to Kafka broker: {}", broker);
-        let mut config = ClientConfig::new();
-        let client_config = config.set("bootstrap.servers", broker.clone());
-        let consumer: BaseConsumer = client_config
-            .set("group.id", "technical")
-            .create()
-            .map_err(|e| e.to_string())?;
-        let admin_client =
-            AdminClient::from_config(&client_config).map_err(|err| err.to_string())?;
-        let producer: FutureProducer = client_config.create().map_err(|e| e.to_string())?;
-        let stream_consumer: StreamConsumer = client_config.create().map_err(|e| e.to_string())?;
+        if !self.uses_sasl() {
+            return Ok(());
+        }
 
-        match consumer.fetch_metadata(None, std::time::Duration::from_secs(5)) {
-            Ok(_) => println!(
-                "Successfully connected and fetched metadata from Kafka broker: {}",
-                broker
-            ),
-            Err(e) => {
-                return Err(format!(
-                    "Could not establish connection with kafka broker: {}. {}",
-                    broker, e
-                ))
-            }
+        let mechanism = self.sasl_mechanism.clone().unwrap_or_default();
+        if !SASL_MECHANISMS.contains(&mechanism.to_ascii_uppercase().as_str()) {
+            return Err(format!(
+                "Unknown SASL mechanism: {}. Expected one of {}",
+                mechanism,
+                SASL_MECHANISMS.join(", ")
+            ));
         }
-        println!("Connected to Kafka broker: {}", broker);
 
-        KafkaConnection::store_broker(&broker, name, schema_registry)
-            .await
-            .map_err(|e| e.to_string())?;
+        if self.sasl_username.clone().unwrap_or_default().is_empty() {
+            return Err("SASL username cannot be empty".to_string());
+        }
 
-        let mut kafka_consumer_guard = KafkaConnection::get_consumer_instance().lock().await;
-        match &*kafka_consumer_guard {
-            Some(_) => {}
-            None => {
-                *kafka_consumer_guard = Some(consumer);
+        Ok(())
+    }
+
+    fn validate_ssl_files(&self) -> Result<(), String> {
+        let files = [
+            ("CA bundle", &self.ssl_ca_location),
+            ("Client certificate", &self.ssl_certificate_location),
+            ("Client key", &self.ssl_key_location),
+        ];
+
+        for (label, location) in files {
+            if let Some(location) = non_empty(location) {
+                if !Path::new(&location).is_file() {
+                    return Err(format!("{} file not found: {}", label, location));
+                }
             }
         }
 
-        let mut kafka_admin_client_guard =
-            KafkaConnection::get_admin_client_instance().lock().await;
+        Ok(())
+    }
 
-        match &*kafka_admin_client_guard {
-            Some(_) => {}
-            None => {
-                *kafka_admin_client_guard = Some(admin_client);
-            }
+    pub fn apply(&self, config: &mut ClientConfig) {
+        if let Some(protocol) = self.protocol() {
+            config.set("security.protocol", protocol);
+        }
+
+        if self.uses_sasl() {
+            config
+                .set(
+                    "sasl.mechanism",
+                    self.sasl_mechanism
+                        .clone()
+                        .unwrap_or_default()
+                        .to_ascii_uppercase(),
+                )
+                .set(
+                    "sasl.username",
+                    self.sasl_username.clone().unwrap_or_default(),
+                )
+                .set(
+                    "sasl.password",
+                    self.sasl_password.clone().unwrap_or_default(),
+                );
         }
 
-        let mut kafka_producer_guard = KafkaConnection::get_producer_instance().lock().await;
+        if self.uses_ssl() {
+            let locations = [
+                ("ssl.ca.location", &self.ssl_ca_location),
+                ("ssl.certificate.location", &self.ssl_certificate_location),
+                ("ssl.key.location", &self.ssl_key_location),
+                ("ssl.key.password", &self.ssl_key_password),
+            ];
+
+            for (key, value) in locations {
+                if let Some(value) = non_empty(value) {
+                    config.set(key, value);
+                }
+            }
 
-        match &*kafka_producer_guard {
-            Some(_) => {}
-            None => {
-                *kafka_producer_guard = Some(producer);
+            if self.ssl_disable_hostname_verification {
+                config.set("ssl.endpoint.identification.algorithm", "none");
             }
         }
+    }
+}
 
-        let mut kafka_stream_consumer_guard =
-            KafkaConnection::get_stream_consumer_instance().lock().await;
+pub fn non_empty(value: &Option<String>) -> Option<String> {
+    value.clone().filter(|value| !value.is_empty())
+}
 
-        match &*kafka_stream_consumer_guard {
-            Some(_) => {}
-            None => {
-                *kafka_stream_consumer_guard = Some(stream_consumer);
-            }
+#[derive(Default)]
+struct ConnectionProbeContext {
+    errors: StdMutex<Vec<(KafkaError, String)>>,
+}
+
+impl ClientContext for ConnectionProbeContext {
+    fn error(&self, error: KafkaError, reason: &str) {
+        println!("Kafka client error: {}: {}", error, reason);
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push((error, reason.to_string()));
         }
+    }
+}
 
-        let mut broker_guard = KafkaConnection::get_broker().lock().await;
-        match &*broker_guard {
-            Some(_) => {}
-            None => {
-                *broker_guard = Some(broker.clone());
-            }
+impl ConsumerContext for ConnectionProbeContext {}
+
+impl ConnectionProbeContext {
+    fn describe_failure(&self, broker: &str, error: KafkaError) -> String {
+        let errors = match self.errors.lock() {
+            Ok(errors) => errors.clone(),
+            Err(_) => vec![],
+        };
+
+        let tls_error = errors.iter().find(|(error, reason)| {
+            error.rdkafka_error_code() == Some(RDKafkaErrorCode::SSL)
+                || reason.contains("SSL handshake")
+                || reason.contains("certificate verify failed")
+        });
+        if let Some((_, reason)) = tls_error {
+            return format!(
+                "TLS handshake with kafka broker {} failed: {}. Check CA bundle, client certificate and key",
+                broker, reason
+            );
+        }
+
+        let auth_error = errors.iter().find(|(error, _)| {
+            error.rdkafka_error_code() == Some(RDKafkaErrorCode::Authentication)
+        });
+        if let Some((_, reason)) = auth_error {
+            return format!(
+                "Authentication with kafka broker {} failed: {}",
+                broker, reason
+            );
+        }
+
+        match errors.last() {
+            Some((_, reason)) => format!(
+                "Could not establish connection with kafka broker: {}. {}. {}",
+                broker, error, reason
+            ),
+            None => format!(
+                "Could not establish connection with kafka broker: {}. {}",
+                broker, error
+            ),
         }
+    }
+}
+
+pub struct ClusterConnection {
+    pub consumer: BaseConsumer,
+    pub producer: FutureProducer,
+    pub admin_client: AdminClient<DefaultClientContext>,
+    pub schema_registry: Option<RegistryConnection>,
+    client_config: ClientConfig,
+}
 
-        Ok(true)
+impl ClusterConnection {
+    pub fn get_client_config(&self) -> ClientConfig {
+        self.client_config.clone()
     }
+}
 
-    pub async fn get_saved_brokers() -> Result<Vec<ConnectionItem>, String> {
-        let local_data_dir = tauri::api::path::local_data_dir()
-            .unwrap_or(PathBuf::new())
-            .display()
-            .to_string();
+pub static CONNECTIONS: Lazy<Mutex<HashMap<String, Arc<ClusterConnection>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
-        let file_path = PathBuf::from(local_data_dir).join("komprender/brokers.json");
+static WINDOW_CONNECTIONS: Lazy<Mutex<HashMap<String, String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
-        if file_path.exists() {
-            let mut file = File::open(&file_path).await.map_err(|e| e.to_string())?;
-            let mut contents = String::new();
-            file.read_to_string(&mut contents)
-                .await
-                .map_err(|e| e.to_string())?;
+impl KafkaConnection {
+    fn get_connections() -> &'static Mutex<HashMap<String, Arc<ClusterConnection>>> {
+        &CONNECTIONS
+    }
 
-            let file_contents: Connections =
-                serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    pub async fn get(connection_id: &str) -> Result<Arc<ClusterConnection>, String> {
+        let connections = KafkaConnection::get_connections().lock().await;
 
-            Ok(file_contents.items)
-        } else {
-            Ok(vec![])
+        match connections.get(connection_id) {
+            Some(connection) => Ok(connection.clone()),
+            None => Err("Kafka connection not established".to_string()),
         }
     }
 
-    async fn store_broker(host: &str, name: &str, schema_registry: &str) -> Result<(), String> {
-        let local_data_dir = tauri::api::path::local_data_dir()
-            .unwrap_or(PathBuf::new())
-            .display()
-            .to_string();
-
-        let file_path = PathBuf::from(local_data_dir).join("komprender/brokers.json");
-
-        if file_path.exists() {
-            let mut file = File::open(&file_path).await.map_err(|e| e.to_string())?;
-            let mut contents = String::new();
-            file.read_to_string(&mut contents)
-                .await
-                .map_err(|e| e.to_string())?;
-
-            let mut file_contents: Connections =
-                serde_json::from_str(&contents).map_err(|e| e.to_string())?;
-
-            if !file_contents.items.iter().any(|item| {
-                &item.kafka_broker.to_string() == &host.to_string()
-                    && &item.schema_registry.to_string() == &schema_registry.to_string()
-            }) {
-                file_contents.items.push(ConnectionItem {
-                    kafka_broker: host.to_string(),
-                    schema_registry: schema_registry.to_string(),
-                    name: name.to_string(),
-                });
-            }
+    pub async fn get_client_config(connection_id: &str) -> Result<ClientConfig, String> {
+        let connection = KafkaConnection::get(connection_id).await?;
+
+        Ok(connection.get_client_config())
+    }
+
+    pub async fn disconnect(connection_id: &str) -> Result<(), String> {
+        let mut connections = KafkaConnection::get_connections().lock().await;
+        connections.remove(connection_id);
+
+        Ok(())
+    }
+
+    /// Records the connection a window uses and returns the one it replaces, if any.
+    pub async fn bind_window(window_label: &str, connection_id: &str) -> Option<String> {
+        let mut windows = WINDOW_CONNECTIONS.lock().await;
 
-            let serialized =
-                serde_json::to_string_pretty(&file_contents).map_err(|e| e.to_string())?;
-            fs::write(file_path, serialized)
-                .await
-                .map_err(|e| e.to_string())?;
+        windows.insert(window_label.to_string(), connection_id.to_string())
+    }
+
+    pub async fn unbind_window(window_label: &str) -> Option<String> {
+        let mut windows = WINDOW_CONNECTIONS.lock().await;
+
+        windows.remove(window_label)
+    }
+
+    pub async fn connect(
+        host: &str,
+        name: &str,
+        schema_registry: &str,
+        schema_registry_connection: Option<RegistryConnection>,
+        schema_registry_security: &SchemaRegistrySecurity,
+        security: &KafkaSecurity,
+        properties: &BTreeMap<String, String>,
+    ) -> Result<String, String> {
+        if host.is_empty() {
+            return Err("Broker cannot be empty".to_string());
+        }
+
+        if name.is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        let broker = if !host.contains(':') {
+            format!("{}:9092", host)
         } else {
-            let data = Connections {
-                items: vec![ConnectionItem {
-                    kafka_broker: host.to_string(),
-                    schema_registry: schema_registry.to_string(),
-                    name: name.to_string(),
-                }],
-            };
-
-            let serialized = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
-            if let Some(parent) = file_path.parent() {
-                fs::create_dir_all(parent)
-                    .await
-                    .map_err(|e| e.to_string())?;
-            }
-            let mut file = File::create(&file_path).await.map_err(|e| e.to_string())?;
-            file.write_all(serialized.as_bytes())
-                .await
-                .map_err(|e| e.to_string())?;
+            host.to_string()
+        };
+        security.validate()?;
+        validate_properties(properties)?;
+
+        println!("Connecting to Kafka broker: {}", broker);
+        let mut base_config = ClientConfig::new();
+        base_config.set("bootstrap.servers", broker.clone());
+        security.apply(&mut base_config);
+        for (key, value) in properties {
+            base_config.set(key, value);
         }
 
-        Ok(())
+        let mut config = base_config.clone();
+        let client_config = config.set("group.id", "technical");
+        let consumer: BaseConsumer = client_config.create().map_err(|e| e.to_string())?;
+        let admin_client =
+            AdminClient::from_config(&client_config).map_err(|err| err.to_string())?;
+        let producer: FutureProducer = client_config.create().map_err(|e| e.to_string())?;
+
+        let probe: BaseConsumer<ConnectionProbeContext> = client_config
+            .create_with_context(ConnectionProbeContext::default())
+            .map_err(|e| e.to_string())?;
+
+        match probe.fetch_metadata(None, std::time::Duration::from_secs(5)) {
+            Ok(_) => println!(
+                "Successfully connected and fetched metadata from Kafka broker: {}",
+                broker
+            ),
+            Err(e) => return Err(probe.context().describe_failure(&broker, e)),
+        }
+        println!("Connected to Kafka broker: {}", broker);
+
+        ConnectionStore::store_broker(
+            &broker,
+            name,
+            schema_registry,
+            schema_registry_security,
+            security,
+            properties,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let connection_id = generate_connection_id();
+        let connection = ClusterConnection {
+            consumer,
+            producer,
+            admin_client,
+            schema_registry: schema_registry_connection,
+            client_config: base_config,
+        };
+
+        let mut connections = KafkaConnection::get_connections().lock().await;
+        connections.insert(connection_id.clone(), Arc::new(connection));
+        println!(
+            "Registered connection {} ({} active)",
+            connection_id,
+            connections.len()
+        );
+
+        Ok(connection_id)
     }
 }
 
+fn generate_connection_id() -> String {
+    let rng = thread_rng();
+    let random_string: String = rng
+        .sample_iter(&Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect();
+    format!("connection-{}", random_string)
+}
+
 pub fn fulfill_tpl(
     assignment: &mut TopicPartitionList,
     topic_name: &str,
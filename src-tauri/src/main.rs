@@ -1,39 +1,121 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use crate::consumer_commands::{consume_messages, stop_consumers};
-use crate::kafka_connection::{ConnectionItem, KafkaConnection};
-use crate::producer_commands::{produce_message_avro, produce_message_json};
-use crate::schema_registry::{fetch_schema, fetch_sr_subjects, SchemaRegistry};
+use crate::client_properties::{import_client_properties, validate_client_properties};
+use crate::connection_profiles::{export_connection_profiles, import_connection_profiles};
+use crate::connection_store::{
+    delete_saved_broker, duplicate_saved_broker, rename_saved_broker, reorder_saved_brokers,
+    set_saved_broker_tags, update_saved_broker, ConnectionItem, ConnectionStore,
+};
+use crate::consumer_commands::{
+    consume_messages, delete_technical_consumer_groups, stop_consumers,
+};
+use crate::credential_vault::{change_vault_passphrase, lock_vault, unlock_vault, vault_status};
+use crate::deserializers::{fetch_topic_deserializers, save_topic_deserializers};
+use crate::kafka_connection::{KafkaConnection, KafkaSecurity};
+use crate::producer_commands::{
+    produce_message_avro, produce_message_json, produce_message_json_schema,
+    produce_message_protobuf,
+};
+use crate::schema_registry::{
+    fetch_schema, fetch_sr_subjects, fetch_writer_schema, SchemaRegistry, SchemaRegistrySecurity,
+};
 use crate::topic_commands::{create_topic, drop_topics, fetch_topic, fetch_topics};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::api::dialog::confirm;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowEvent, WindowUrl};
 
+mod client_properties;
+mod connection_profiles;
+mod connection_store;
 mod consumer_commands;
+mod credential_vault;
+mod deserializers;
+mod json_schema_format;
 mod kafka_connection;
+mod message_filter;
+mod message_format;
+mod message_headers;
 mod producer_commands;
+mod protobuf_format;
 mod schema_registry;
 mod topic_commands;
 
 const CURRENT_VERSION: &str = "1.3.0";
 
+static WINDOW_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+#[tauri::command]
+async fn connect(
+    window: Window,
+    host: &str,
+    name: &str,
+    schema_registry: &str,
+    schema_registry_security: Option<SchemaRegistrySecurity>,
+    security: Option<KafkaSecurity>,
+    properties: Option<BTreeMap<String, String>>,
+    saved_connection_id: Option<String>,
+) -> Result<String, String> {
+    let mut security = security.unwrap_or_default();
+    let mut schema_registry_security = schema_registry_security.unwrap_or_default();
+    let properties = properties.unwrap_or_default();
+    if let Some(id) = saved_connection_id {
+        ConnectionStore::resolve_secrets(&id, &mut security, &mut schema_registry_security).await?;
+    }
+    let sr_connection = SchemaRegistry::connect(schema_registry, &schema_registry_security).await?;
+    let connection_id = KafkaConnection::connect(
+        host,
+        name,
+        schema_registry,
+        sr_connection,
+        &schema_registry_security,
+        &security,
+        &properties,
+    )
+    .await?;
+
+    if let Some(previous) = KafkaConnection::bind_window(window.label(), &connection_id).await {
+        if let Err(e) = close_connection(&previous).await {
+            eprintln!("Error closing previous connection {}: {}", previous, e);
+        }
+    }
+
+    Ok(connection_id)
+}
+
 #[tauri::command]
-async fn connect(host: &str, name: &str, schema_registry: &str) -> Result<bool, String> {
-    SchemaRegistry::connect(schema_registry).await?;
-    KafkaConnection::connect(host, name, schema_registry).await
+async fn disconnect(window: Window, connection_id: &str) -> Result<(), String> {
+    KafkaConnection::unbind_window(window.label()).await;
+    close_connection(connection_id).await
+}
+
+async fn close_connection(connection_id: &str) -> Result<(), String> {
+    println!("Disconnecting {} from Kafka and SR", connection_id);
+
+    stop_consumers(connection_id.to_string()).await;
+    KafkaConnection::disconnect(connection_id).await
 }
 
 #[tauri::command]
-async fn disconnect() -> Result<(), String> {
-    println!("Disconnecting from Kafka and SR");
+async fn open_window(app_handle: AppHandle) -> Result<(), String> {
+    let label = format!(
+        "komprender-{}",
+        WINDOW_COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+
+    WindowBuilder::new(&app_handle, label, WindowUrl::App("index.html".into()))
+        .title("Komprender")
+        .inner_size(1280.0, 720.0)
+        .build()
+        .map_err(|e| e.to_string())?;
 
-    KafkaConnection::disconnect().await?;
-    SchemaRegistry::disconnect().await
+    Ok(())
 }
 
 #[tauri::command]
-async fn fetch_saved_brokers() -> Result<Vec<ConnectionItem>, String> {
-    KafkaConnection::get_saved_brokers().await
+async fn fetch_saved_brokers(tag: Option<String>) -> Result<Vec<ConnectionItem>, String> {
+    ConnectionStore::get_saved_brokers(tag).await
 }
 
 #[tauri::command]
@@ -69,6 +151,18 @@ async fn check_version(app_handle: AppHandle) -> Result<(), String> {
 async fn main() {
     let _ = fix_path_env::fix();
     tauri::Builder::default()
+        .on_window_event(|event| {
+            if let WindowEvent::Destroyed = event.event() {
+                let label = event.window().label().to_string();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(connection_id) = KafkaConnection::unbind_window(&label).await {
+                        if let Err(e) = close_connection(&connection_id).await {
+                            eprintln!("Error closing connection of window {}: {}", label, e);
+                        }
+                    }
+                });
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             connect,
             check_version,
@@ -78,12 +172,33 @@ async fn main() {
             create_topic,
             produce_message_avro,
             produce_message_json,
+            produce_message_json_schema,
+            produce_message_protobuf,
+            fetch_topic_deserializers,
+            save_topic_deserializers,
             consume_messages,
             stop_consumers,
+            delete_technical_consumer_groups,
             fetch_sr_subjects,
             fetch_schema,
+            fetch_writer_schema,
             disconnect,
             fetch_saved_brokers,
+            update_saved_broker,
+            rename_saved_broker,
+            delete_saved_broker,
+            duplicate_saved_broker,
+            reorder_saved_brokers,
+            set_saved_broker_tags,
+            export_connection_profiles,
+            import_connection_profiles,
+            vault_status,
+            unlock_vault,
+            lock_vault,
+            change_vault_passphrase,
+            open_window,
+            import_client_properties,
+            validate_client_properties,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
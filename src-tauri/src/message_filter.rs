@@ -0,0 +1,159 @@
+use rdkafka::message::Headers;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serde_json_path::JsonPath;
+
+#[derive(Deserialize)]
+pub struct HeaderFilter {
+    key: String,
+    value: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct MessageFilter {
+    key_equals: Option<String>,
+    key_regex: Option<String>,
+    value_contains: Option<String>,
+    /// Matches when the query selects at least one node of the decoded value, e.g.
+    /// `$.items[?@.price > 10]`.
+    value_json_path: Option<String>,
+    headers: Vec<HeaderFilter>,
+    partition_from: Option<i32>,
+    partition_to: Option<i32>,
+    timestamp_from: Option<i64>,
+    timestamp_to: Option<i64>,
+}
+
+pub struct CompiledFilter {
+    key_equals: Option<String>,
+    key_regex: Option<Regex>,
+    value_contains: Option<String>,
+    value_json_path: Option<JsonPath>,
+    headers: Vec<HeaderFilter>,
+    partition_from: Option<i32>,
+    partition_to: Option<i32>,
+    timestamp_from: Option<i64>,
+    timestamp_to: Option<i64>,
+}
+
+impl MessageFilter {
+    pub fn compile(self) -> Result<CompiledFilter, String> {
+        let key_regex = match non_empty(self.key_regex) {
+            Some(pattern) => Some(
+                Regex::new(&pattern)
+                    .map_err(|e| format!("Invalid key regex {}: {}", pattern, e))?,
+            ),
+            None => None,
+        };
+
+        let value_json_path = match non_empty(self.value_json_path) {
+            Some(query) => Some(
+                JsonPath::parse(&query)
+                    .map_err(|e| format!("Invalid JSONPath {}: {}", query, e))?,
+            ),
+            None => None,
+        };
+
+        if self.headers.iter().any(|header| header.key.is_empty()) {
+            return Err("Header filter name cannot be empty".to_string());
+        }
+
+        Ok(CompiledFilter {
+            key_equals: non_empty(self.key_equals),
+            key_regex,
+            value_contains: non_empty(self.value_contains),
+            value_json_path,
+            headers: self.headers,
+            partition_from: self.partition_from,
+            partition_to: self.partition_to,
+            timestamp_from: self.timestamp_from,
+            timestamp_to: self.timestamp_to,
+        })
+    }
+}
+
+impl CompiledFilter {
+    /// Checks everything that is known before the record is decoded.
+    pub fn matches_metadata<H: Headers>(
+        &self,
+        partition: i32,
+        timestamp: Option<i64>,
+        headers: Option<&H>,
+    ) -> bool {
+        if self.partition_from.map_or(false, |from| partition < from)
+            || self.partition_to.map_or(false, |to| partition > to)
+        {
+            return false;
+        }
+
+        if self.timestamp_from.is_some() || self.timestamp_to.is_some() {
+            let timestamp = match timestamp {
+                Some(timestamp) => timestamp,
+                None => return false,
+            };
+
+            if self.timestamp_from.map_or(false, |from| timestamp < from)
+                || self.timestamp_to.map_or(false, |to| timestamp > to)
+            {
+                return false;
+            }
+        }
+
+        self.headers.iter().all(|filter| match headers {
+            Some(headers) => headers.iter().any(|header| {
+                header.key == filter.key
+                    && filter
+                        .value
+                        .as_ref()
+                        .map_or(true, |value| header.value == Some(value.as_bytes()))
+            }),
+            None => false,
+        })
+    }
+
+    pub fn matches_data(&self, key: &JsonValue, value: &JsonValue) -> bool {
+        if self.key_equals.is_some() || self.key_regex.is_some() {
+            let key = match as_text(key) {
+                Some(key) => key,
+                None => return false,
+            };
+
+            if self
+                .key_equals
+                .as_ref()
+                .map_or(false, |expected| *expected != key)
+                || self
+                    .key_regex
+                    .as_ref()
+                    .map_or(false, |regex| !regex.is_match(&key))
+            {
+                return false;
+            }
+        }
+
+        if let Some(substring) = &self.value_contains {
+            if !as_text(value).map_or(false, |value| value.contains(substring.as_str())) {
+                return false;
+            }
+        }
+
+        match &self.value_json_path {
+            Some(path) => !path.query(value).is_empty(),
+            None => true,
+        }
+    }
+}
+
+fn as_text(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
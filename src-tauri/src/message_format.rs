@@ -0,0 +1,142 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+const MAGIC_BYTES: [(&[u8], &str); 10] = [
+    (&[0x1f, 0x8b], "application/gzip"),
+    (&[0x28, 0xb5, 0x2f, 0xfd], "application/zstd"),
+    (&[0x04, 0x22, 0x4d, 0x18], "application/x-lz4"),
+    (
+        &[0xff, 0x06, 0x00, 0x00, 0x73, 0x4e, 0x61, 0x50, 0x70, 0x59],
+        "application/x-snappy-framed",
+    ),
+    (&[0x50, 0x4b, 0x03, 0x04], "application/zip"),
+    (&[0x25, 0x50, 0x44, 0x46], "application/pdf"),
+    (&[0x89, 0x50, 0x4e, 0x47], "image/png"),
+    (&[0xff, 0xd8, 0xff], "image/jpeg"),
+    (&[0x47, 0x49, 0x46, 0x38], "image/gif"),
+    (&[0x4f, 0x62, 0x6a, 0x01], "application/avro"),
+];
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Null,
+    Text,
+    Json,
+    Avro,
+    Protobuf,
+    #[serde(rename = "json_schema")]
+    JsonSchema,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+    Cbor,
+    Hex,
+    Base64,
+    Integer,
+    Uuid,
+}
+
+pub struct RenderedData {
+    pub value: JsonValue,
+    pub format: DataFormat,
+    pub content_type: Option<String>,
+}
+
+impl RenderedData {
+    pub fn null() -> RenderedData {
+        RenderedData {
+            value: JsonValue::Null,
+            format: DataFormat::Null,
+            content_type: None,
+        }
+    }
+}
+
+pub fn render_key(bytes: Option<&[u8]>) -> RenderedData {
+    let bytes = match bytes {
+        Some(bytes) => bytes,
+        None => return RenderedData::null(),
+    };
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => RenderedData {
+            value: text.into(),
+            format: DataFormat::Text,
+            content_type: Some("text/plain".to_string()),
+        },
+        Err(_) => RenderedData {
+            value: to_hex(bytes).into(),
+            format: DataFormat::Hex,
+            content_type: Some(detect_content_type(bytes).to_string()),
+        },
+    }
+}
+
+pub fn render_value(bytes: &[u8]) -> RenderedData {
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(_) => {
+            return RenderedData {
+                value: STANDARD.encode(bytes).into(),
+                format: DataFormat::Base64,
+                content_type: Some(detect_content_type(bytes).to_string()),
+            }
+        }
+    };
+
+    match serde_json::from_str::<JsonValue>(text) {
+        Ok(json) => RenderedData {
+            value: json,
+            format: DataFormat::Json,
+            content_type: Some("application/json".to_string()),
+        },
+        Err(_) => RenderedData {
+            value: text.into(),
+            format: DataFormat::Text,
+            content_type: Some("text/plain".to_string()),
+        },
+    }
+}
+
+pub fn detect_content_type(bytes: &[u8]) -> &'static str {
+    if let Some((_, content_type)) = MAGIC_BYTES
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+    {
+        return content_type;
+    }
+
+    if has_confluent_framing(bytes) {
+        return "application/vnd.confluent.schema-registry";
+    }
+
+    "application/octet-stream"
+}
+
+pub fn has_confluent_framing(bytes: &[u8]) -> bool {
+    bytes.len() > 5 && bytes[0] == 0
+}
+
+pub fn schema_id(bytes: &[u8]) -> Option<u32> {
+    if !has_confluent_framing(bytes) {
+        return None;
+    }
+
+    Some(u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]))
+}
+
+/// Prefixes a serialized payload with the magic byte and schema id expected by Schema Registry
+/// aware deserializers.
+pub fn frame_with_schema_id(id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(payload.len() + 5);
+    framed.push(0);
+    framed.extend_from_slice(&id.to_be_bytes());
+    framed.extend_from_slice(payload);
+
+    framed
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
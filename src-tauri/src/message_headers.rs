@@ -0,0 +1,87 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rdkafka::message::{Header, Headers, OwnedHeaders};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeaderEncoding {
+    #[default]
+    String,
+    Base64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MessageHeader {
+    key: String,
+    value: Option<String>,
+    #[serde(default)]
+    encoding: HeaderEncoding,
+}
+
+impl MessageHeader {
+    fn value_bytes(&self) -> Result<Option<Vec<u8>>, String> {
+        let value = match &self.value {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        match self.encoding {
+            HeaderEncoding::String => Ok(Some(value.as_bytes().to_vec())),
+            HeaderEncoding::Base64 => STANDARD
+                .decode(value)
+                .map(Some)
+                .map_err(|e| format!("Header {} is not valid base64: {}", self.key, e)),
+        }
+    }
+}
+
+pub fn to_owned_headers(headers: &[MessageHeader]) -> Result<Option<OwnedHeaders>, String> {
+    if headers.is_empty() {
+        return Ok(None);
+    }
+
+    let mut owned_headers = OwnedHeaders::new_with_capacity(headers.len());
+    for header in headers {
+        if header.key.is_empty() {
+            return Err("Header name cannot be empty".to_string());
+        }
+
+        if header.key.contains('\0') {
+            return Err(format!("Header name {} contains a NUL byte", header.key));
+        }
+
+        let value = header.value_bytes()?;
+        owned_headers = owned_headers.insert(Header {
+            key: &header.key,
+            value: value.as_deref(),
+        });
+    }
+
+    Ok(Some(owned_headers))
+}
+
+pub fn from_headers<H: Headers>(headers: Option<&H>) -> Vec<MessageHeader> {
+    let headers = match headers {
+        Some(headers) => headers,
+        None => return vec![],
+    };
+
+    headers
+        .iter()
+        .map(|header| {
+            let (value, encoding) = match header.value {
+                None => (None, HeaderEncoding::String),
+                Some(bytes) => match std::str::from_utf8(bytes) {
+                    Ok(value) => (Some(value.to_string()), HeaderEncoding::String),
+                    Err(_) => (Some(STANDARD.encode(bytes)), HeaderEncoding::Base64),
+                },
+            };
+
+            MessageHeader {
+                key: header.key.to_string(),
+                value,
+                encoding,
+            }
+        })
+        .collect()
+}
@@ -1,84 +1,423 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use apache_avro::types::Value;
+use prost_reflect::FileDescriptor;
 use rdkafka::producer::FutureRecord;
 use schema_registry_converter::async_impl::avro::AvroEncoder;
-use schema_registry_converter::async_impl::schema_registry::SrSettings;
-use schema_registry_converter::schema_registry_common::SubjectNameStrategy::RecordNameStrategy;
+use schema_registry_converter::schema_registry_common::SubjectNameStrategy::{
+    RecordNameStrategy, TopicNameStrategy, TopicRecordNameStrategy,
+};
+use schema_registry_converter::schema_registry_common::{get_subject, SubjectNameStrategy};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map as JsonMap, Value as JsonValue};
 use tokio::time::Duration;
 
+use crate::json_schema_format;
 use crate::kafka_connection::KafkaConnection;
+use crate::message_format::frame_with_schema_id;
+use crate::message_headers::{to_owned_headers, MessageHeader};
+use crate::protobuf_format::{
+    compile_local_file, compile_schema, encode_from_json, find_message, import_directory,
+    imported_files,
+};
+use crate::schema_registry::{RegistryConnection, SchemaReference, SchemaRegistry};
+
+#[derive(Serialize)]
+pub struct DeliveryReport {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    timestamp: i64,
+    serialized_key_size: usize,
+    serialized_value_size: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct RecordOptions {
+    partition: Option<i32>,
+    timestamp: Option<i64>,
+    null_key: bool,
+    tombstone: bool,
+}
 
 #[tauri::command]
 pub async fn produce_message_avro(
+    connection_id: &str,
     topic: &str,
     payload: &str,
     schema_name: &str,
     key: &str,
-) -> Result<(), String> {
-    println!("{}", schema_name);
-    let parsed_json: JsonValue = serde_json::from_str(payload).map_err(|e| {
-        println!("Error parsing JSON: {}", e);
-        e.to_string()
-    })?;
-    let sr_settings = SrSettings::new_builder(String::from("http://localhost:8081"))
-        .set_timeout(Duration::from_secs(5))
-        .build()
-        .unwrap();
-    let key_strategy = RecordNameStrategy(schema_name.to_string());
-
-    let encoder = AvroEncoder::new(sr_settings.clone());
-
-    let bytes = match parsed_json {
-        serde_json::Value::Object(map) => encoder.encode_struct(map, &key_strategy).await,
-        _ => return Err("Payload must be a JSON object".to_string().into()),
+    value_strategy: Option<String>,
+    key_schema_name: Option<String>,
+    key_strategy: Option<String>,
+    headers: Option<Vec<MessageHeader>>,
+    options: Option<RecordOptions>,
+) -> Result<DeliveryReport, String> {
+    let options = options.unwrap_or_default();
+    let sr_settings = SchemaRegistry::get_settings(connection_id).await?;
+    let encoder = AvroEncoder::new(sr_settings);
+
+    let bytes = if options.tombstone {
+        None
+    } else {
+        let parsed_json: JsonValue =
+            serde_json::from_str(payload).map_err(|e| format!("Invalid JSON payload: {}", e))?;
+        let value_strategy =
+            subject_name_strategy(value_strategy.as_deref(), topic, schema_name, false)?;
+
+        match parsed_json {
+            serde_json::Value::Object(map) => Some(
+                encoder
+                    .encode_struct(map, &value_strategy)
+                    .await
+                    .map_err(|e| format!("Error encoding Avro value: {}", e))?,
+            ),
+            _ => return Err("Payload must be a JSON object".to_string()),
+        }
     };
 
-    if let Ok(bytes) = bytes {
-        println!("success encoding");
-        let producer = KafkaConnection::get_producer_instance().lock().await;
-        if let Some(producer) = &*producer {
-            let produce_future = producer.send(
-                FutureRecord::to(topic).key(key).payload(&bytes),
-                Duration::from_secs(10),
-            );
-
-            match produce_future.await {
-                Ok(delivery) => println!("Sent: {:?}", delivery),
-                Err((e, _)) => println!("Error: {:?}", e),
-            }
+    let key_strategy = key_strategy.filter(|strategy| !strategy.is_empty());
+    let key_bytes = match key_strategy {
+        _ if options.null_key => None,
+        Some(strategy) => {
+            let key_json: JsonValue =
+                serde_json::from_str(key).map_err(|e| format!("Invalid JSON key: {}", e))?;
+            let key_strategy = subject_name_strategy(
+                Some(&strategy),
+                topic,
+                key_schema_name.as_deref().unwrap_or_default(),
+                true,
+            )?;
+
+            Some(
+                encoder
+                    .encode_struct(key_json, &key_strategy)
+                    .await
+                    .map_err(|e| format!("Error encoding Avro key: {}", e))?,
+            )
         }
-    } else if let Err(e) = bytes {
-        println!("Error encoding Avro: {}", e);
-        return Err(e.to_string());
+        None => Some(key.as_bytes().to_vec()),
+    };
+
+    send_record(
+        connection_id,
+        topic,
+        key_bytes.as_deref(),
+        bytes.as_deref(),
+        &headers.unwrap_or_default(),
+        &options,
+    )
+    .await
+}
+
+fn subject_name_strategy(
+    strategy: Option<&str>,
+    topic: &str,
+    record_name: &str,
+    is_key: bool,
+) -> Result<SubjectNameStrategy, String> {
+    let strategy = strategy.filter(|strategy| !strategy.is_empty());
+    let requires_record_name = !matches!(strategy, Some("TopicName"));
+    if requires_record_name && record_name.is_empty() {
+        return Err(format!(
+            "{} schema name is required for this subject name strategy",
+            if is_key { "Key" } else { "Value" }
+        ));
     }
 
-    Ok(())
+    match strategy {
+        Some("TopicName") => Ok(TopicNameStrategy(topic.to_string(), is_key)),
+        Some("RecordName") | None => Ok(RecordNameStrategy(record_name.to_string())),
+        Some("TopicRecordName") => Ok(TopicRecordNameStrategy(
+            topic.to_string(),
+            record_name.to_string(),
+        )),
+        Some(strategy) => Err(format!(
+            "Unknown subject name strategy: {}. Expected one of TopicName, RecordName, TopicRecordName",
+            strategy
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn produce_message_json_schema(
+    connection_id: &str,
+    topic: &str,
+    payload: &str,
+    key: &str,
+    schema_name: Option<String>,
+    value_strategy: Option<String>,
+    headers: Option<Vec<MessageHeader>>,
+    options: Option<RecordOptions>,
+) -> Result<DeliveryReport, String> {
+    let options = options.unwrap_or_default();
+
+    let bytes = if options.tombstone {
+        None
+    } else {
+        let parsed_json: JsonValue =
+            serde_json::from_str(payload).map_err(|e| format!("Invalid JSON payload: {}", e))?;
+        let value_strategy = value_strategy
+            .filter(|strategy| !strategy.is_empty())
+            .unwrap_or_else(|| "TopicName".to_string());
+        let value_strategy = subject_name_strategy(
+            Some(&value_strategy),
+            topic,
+            schema_name.as_deref().unwrap_or_default(),
+            false,
+        )?;
+        let subject = get_subject(&value_strategy).map_err(|e| e.to_string())?;
+
+        let registry = SchemaRegistry::get_connection(connection_id).await?;
+        let (id, registered) = registry.latest_schema(&subject).await?;
+        if registered.schema_type() != "JSON" {
+            return Err(format!(
+                "Subject {} holds a {} schema, not a JSON Schema",
+                subject,
+                registered.schema_type()
+            ));
+        }
+        let references = registry.resolve_references(&registered.references).await?;
+        json_schema_format::validate(&registered.schema, references, &parsed_json)?;
+
+        let serialized = serde_json::to_vec(&parsed_json).map_err(|e| e.to_string())?;
+        Some(frame_with_schema_id(id, &serialized))
+    };
+
+    let key = if options.null_key {
+        None
+    } else {
+        Some(key.as_bytes())
+    };
+
+    send_record(
+        connection_id,
+        topic,
+        key,
+        bytes.as_deref(),
+        &headers.unwrap_or_default(),
+        &options,
+    )
+    .await
 }
 
 #[tauri::command]
-pub async fn produce_message_json(topic: &str, payload: &str, key: &str) -> Result<(), String> {
-    let producer = KafkaConnection::get_producer_instance().lock().await;
-    if let Some(producer) = &*producer {
-        let produce_future = producer.send(
-            FutureRecord::to(topic).key(key).payload(payload),
-            Duration::from_secs(10),
-        );
-
-        return match produce_future.await {
-            Ok(delivery) => {
-                println!("Sent: {:?}", delivery);
-                Ok(())
+pub async fn produce_message_protobuf(
+    connection_id: &str,
+    topic: &str,
+    payload: &str,
+    key: &str,
+    subject: Option<String>,
+    proto_file: Option<String>,
+    message_name: Option<String>,
+    headers: Option<Vec<MessageHeader>>,
+    options: Option<RecordOptions>,
+) -> Result<DeliveryReport, String> {
+    let options = options.unwrap_or_default();
+
+    let bytes = if options.tombstone {
+        None
+    } else {
+        let registry = SchemaRegistry::get_connection(connection_id).await?;
+        let subject = subject
+            .filter(|subject| !subject.is_empty())
+            .unwrap_or_else(|| format!("{}-value", topic));
+
+        let (id, file) = match proto_file.filter(|proto_file| !proto_file.is_empty()) {
+            Some(proto_file) => {
+                let file = compile_local_file(&proto_file)?;
+                let references = lookup_local_imports(&registry, &proto_file, &file).await?;
+                let source = tokio::fs::read_to_string(&proto_file)
+                    .await
+                    .map_err(|e| format!("Could not read {}: {}", proto_file, e))?;
+                let schema = registry
+                    .lookup_schema(&subject, "PROTOBUF", &source, &references)
+                    .await?;
+
+                (schema.id, file)
             }
-            Err((e, _)) => {
-                let err = format!("Error producing message: {:?}", e);
-                println!("{}", err);
+            None => {
+                let (id, registered) = registry.latest_schema(&subject).await?;
+                if registered.schema_type() != "PROTOBUF" {
+                    return Err(format!(
+                        "Subject {} holds a {} schema, not a Protobuf schema",
+                        subject,
+                        registered.schema_type()
+                    ));
+                }
+                let references = registry.resolve_references(&registered.references).await?;
 
-                Err(err)
+                (id, compile_schema(&registered.schema, references)?)
             }
         };
+
+        let descriptor = find_message(&file, message_name.as_deref())?;
+        let serialized = encode_from_json(&descriptor, payload)?;
+        Some(frame_with_schema_id(id, &serialized))
+    };
+
+    let key = if options.null_key {
+        None
+    } else {
+        Some(key.as_bytes())
+    };
+
+    send_record(
+        connection_id,
+        topic,
+        key,
+        bytes.as_deref(),
+        &headers.unwrap_or_default(),
+        &options,
+    )
+    .await
+}
+
+/// Resolves the imports of a local `.proto` file to registry references, expecting every
+/// imported file to be registered under a subject named after its import path, as the
+/// Confluent serializers do.
+async fn lookup_local_imports(
+    registry: &RegistryConnection,
+    proto_file: &str,
+    file: &FileDescriptor,
+) -> Result<Vec<SchemaReference>, String> {
+    let directory = import_directory(Path::new(proto_file));
+    let mut versions: HashMap<String, u32> = HashMap::new();
+    let references_of = |file: &FileDescriptor, versions: &HashMap<String, u32>| {
+        file.dependencies()
+            .filter_map(|dependency| {
+                versions
+                    .get(dependency.name())
+                    .map(|version| SchemaReference {
+                        name: dependency.name().to_string(),
+                        subject: dependency.name().to_string(),
+                        version: *version,
+                    })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for imported in imported_files(file) {
+        let name = imported.name().to_string();
+        let source = tokio::fs::read_to_string(directory.join(&name))
+            .await
+            .map_err(|e| format!("Could not read import {}: {}", name, e))?;
+        let schema = registry
+            .lookup_schema(
+                &name,
+                "PROTOBUF",
+                &source,
+                &references_of(&imported, &versions),
+            )
+            .await
+            .map_err(|e| format!("Could not resolve import {}: {}", name, e))?;
+        let version = schema
+            .version
+            .ok_or_else(|| format!("Schema Registry returned no version for {}", name))?;
+
+        versions.insert(name, version);
+    }
+
+    Ok(references_of(file, &versions))
+}
+
+#[tauri::command]
+pub async fn produce_message_json(
+    connection_id: &str,
+    topic: &str,
+    payload: &str,
+    key: &str,
+    headers: Option<Vec<MessageHeader>>,
+    options: Option<RecordOptions>,
+) -> Result<DeliveryReport, String> {
+    let options = options.unwrap_or_default();
+    let key = if options.null_key {
+        None
+    } else {
+        Some(key.as_bytes())
+    };
+    let payload = if options.tombstone {
+        None
+    } else {
+        Some(payload.as_bytes())
+    };
+
+    send_record(
+        connection_id,
+        topic,
+        key,
+        payload,
+        &headers.unwrap_or_default(),
+        &options,
+    )
+    .await
+}
+
+async fn send_record(
+    connection_id: &str,
+    topic: &str,
+    key: Option<&[u8]>,
+    payload: Option<&[u8]>,
+    headers: &[MessageHeader],
+    options: &RecordOptions,
+) -> Result<DeliveryReport, String> {
+    if let Some(partition) = options.partition.filter(|partition| *partition < 0) {
+        return Err(format!("Invalid partition: {}", partition));
+    }
+
+    if let Some(timestamp) = options.timestamp.filter(|timestamp| *timestamp < 0) {
+        return Err(format!("Invalid timestamp: {}", timestamp));
+    }
+
+    let owned_headers = to_owned_headers(headers)?;
+    let connection = KafkaConnection::get(connection_id).await?;
+    let timestamp = match options.timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis() as i64,
+    };
+
+    let mut record: FutureRecord<[u8], [u8]> = FutureRecord::to(topic).timestamp(timestamp);
+    if let Some(key) = key {
+        record = record.key(key);
+    }
+    if let Some(payload) = payload {
+        record = record.payload(payload);
     }
+    if let Some(partition) = options.partition {
+        record = record.partition(partition);
+    }
+    if let Some(owned_headers) = owned_headers {
+        record = record.headers(owned_headers);
+    }
+
+    let produce_future = connection.producer.send(record, Duration::from_secs(10));
+
+    match produce_future.await {
+        Ok((partition, offset)) => {
+            println!("Sent to {} [{}] at offset {}", topic, partition, offset);
+
+            Ok(DeliveryReport {
+                topic: topic.to_string(),
+                partition,
+                offset,
+                timestamp,
+                serialized_key_size: key.map_or(0, <[u8]>::len),
+                serialized_value_size: payload.map_or(0, <[u8]>::len),
+            })
+        }
+        Err((e, _)) => {
+            let err = format!("Error producing message: {}", e);
+            println!("{}", err);
 
-    Err("Kafka connection not established".to_string())
+            Err(err)
+        }
+    }
 }
 
 fn _convert_json_map_to_avro(
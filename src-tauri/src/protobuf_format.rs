@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use prost::Message;
+use prost_reflect::{DynamicMessage, FileDescriptor, MessageDescriptor};
+use protox::file::{ChainFileResolver, File, FileResolver, GoogleFileResolver};
+use serde_json::Value as JsonValue;
+
+const SCHEMA_FILE_NAME: &str = "__registry_schema.proto";
+
+struct SourceResolver {
+    sources: BTreeMap<String, String>,
+}
+
+impl FileResolver for SourceResolver {
+    fn open_file(&self, name: &str) -> Result<File, protox::Error> {
+        match self.sources.get(name) {
+            Some(source) => File::from_source(name, source),
+            None => Err(protox::Error::file_not_found(name)),
+        }
+    }
+}
+
+/// Compiles a registry schema together with the sources of the schemas it imports.
+pub fn compile_schema(
+    schema: &str,
+    references: BTreeMap<String, String>,
+) -> Result<FileDescriptor, String> {
+    let mut sources = references;
+    sources.insert(SCHEMA_FILE_NAME.to_string(), schema.to_string());
+
+    let mut resolver = ChainFileResolver::new();
+    resolver.add(SourceResolver { sources });
+    resolver.add(GoogleFileResolver::new());
+
+    let mut compiler = protox::Compiler::with_file_resolver(resolver);
+    compiler.include_imports(true);
+    compiler
+        .open_file(SCHEMA_FILE_NAME)
+        .map_err(|e| format!("Invalid Protobuf schema: {}", e))?;
+
+    compiler
+        .descriptor_pool()
+        .get_file_by_name(SCHEMA_FILE_NAME)
+        .ok_or_else(|| "Protobuf schema did not compile to a file".to_string())
+}
+
+/// Compiles a `.proto` file from disk, resolving imports relative to its directory.
+pub fn compile_local_file(path: &str) -> Result<FileDescriptor, String> {
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .and_then(|f| f.to_str())
+        .ok_or_else(|| format!("Invalid .proto file path: {}", path.display()))?;
+    let directory = import_directory(path);
+
+    let mut compiler = protox::Compiler::new([directory])
+        .map_err(|e| format!("Invalid Protobuf schema: {}", e))?;
+    compiler.include_imports(true);
+    compiler
+        .open_file(file_name)
+        .map_err(|e| format!("Invalid Protobuf schema: {}", e))?;
+
+    compiler
+        .descriptor_pool()
+        .get_file_by_name(file_name)
+        .ok_or_else(|| format!("{} did not compile to a file", path.display()))
+}
+
+/// The directory imports of a local `.proto` file are resolved against.
+pub fn import_directory(path: &Path) -> &Path {
+    match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    }
+}
+
+/// Lists the files a schema imports, directly or transitively, with every file after its own
+/// imports. Well-known types are left out as the registry provides them.
+pub fn imported_files(file: &FileDescriptor) -> Vec<FileDescriptor> {
+    let mut ordered: Vec<FileDescriptor> = vec![];
+    let mut pending = vec![(file.clone(), false)];
+
+    while let Some((current, expanded)) = pending.pop() {
+        if ordered.iter().any(|known| known.name() == current.name()) {
+            continue;
+        }
+
+        if expanded {
+            if current.name() != file.name() {
+                ordered.push(current);
+            }
+            continue;
+        }
+
+        pending.push((current.clone(), true));
+        for dependency in current.dependencies() {
+            if !dependency.name().starts_with("google/protobuf/") {
+                pending.push((dependency, false));
+            }
+        }
+    }
+
+    ordered
+}
+
+/// Finds a message by its full or short name, defaulting to the first message of the file.
+pub fn find_message(
+    file: &FileDescriptor,
+    message_name: Option<&str>,
+) -> Result<MessageDescriptor, String> {
+    let message_name = match message_name.filter(|name| !name.is_empty()) {
+        Some(message_name) => message_name,
+        None => {
+            return file
+                .messages()
+                .next()
+                .ok_or_else(|| "Protobuf schema declares no messages".to_string())
+        }
+    };
+
+    if let Some(message) = file.parent_pool().get_message_by_name(message_name) {
+        return Ok(message);
+    }
+
+    let mut pending: Vec<MessageDescriptor> = file.messages().collect();
+    while let Some(message) = pending.pop() {
+        if message.name() == message_name {
+            return Ok(message);
+        }
+        pending.extend(message.child_messages());
+    }
+
+    Err(format!(
+        "Message {} not found in Protobuf schema",
+        message_name
+    ))
+}
+
+/// Resolves the message a Confluent message index path points at: the first index selects a
+/// top-level message of the file, every following one a nested message of the previous.
+pub fn message_by_indexes(
+    file: &FileDescriptor,
+    indexes: &[i32],
+) -> Result<MessageDescriptor, String> {
+    let invalid = || {
+        format!(
+            "Message index {:?} does not exist in {}",
+            indexes,
+            file.package_name()
+        )
+    };
+    let (first, nested) = indexes.split_first().ok_or_else(invalid)?;
+
+    let mut message = file
+        .messages()
+        .nth(usize::try_from(*first).map_err(|_| invalid())?)
+        .ok_or_else(invalid)?;
+    for index in nested {
+        message = message
+            .child_messages()
+            .nth(usize::try_from(*index).map_err(|_| invalid())?)
+            .ok_or_else(invalid)?;
+    }
+
+    Ok(message)
+}
+
+/// Splits the message index array written after the schema id from the Protobuf payload.
+pub fn split_message_indexes(bytes: &[u8]) -> Result<(Vec<i32>, &[u8]), String> {
+    let (count, mut rest) = read_varint(bytes)?;
+    if count == 0 {
+        return Ok((vec![0], rest));
+    }
+    // Every index takes at least one byte, which bounds the count of a corrupt record.
+    if count < 0 || count as usize > rest.len() {
+        return Err("Invalid Protobuf message index".to_string());
+    }
+
+    let mut indexes = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (index, next) = read_varint(rest)?;
+        indexes.push(index);
+        rest = next;
+    }
+
+    Ok((indexes, rest))
+}
+
+/// Decodes a framed Protobuf payload with the magic byte and schema id already stripped.
+pub fn decode_to_json(file: &FileDescriptor, bytes: &[u8]) -> Result<JsonValue, String> {
+    let (indexes, payload) = split_message_indexes(bytes)?;
+    let descriptor = message_by_indexes(file, &indexes)?;
+    let message = DynamicMessage::decode(descriptor.clone(), payload)
+        .map_err(|e| format!("Could not decode {}: {}", descriptor.full_name(), e))?;
+
+    serde_json::to_value(&message).map_err(|e| e.to_string())
+}
+
+/// Converts a JSON payload into the message and serializes it behind its message index array.
+pub fn encode_from_json(descriptor: &MessageDescriptor, payload: &str) -> Result<Vec<u8>, String> {
+    let mut track = serde_path_to_error::Track::new();
+    let mut deserializer = serde_json::Deserializer::from_str(payload);
+    let message = DynamicMessage::deserialize(
+        descriptor.clone(),
+        serde_path_to_error::Deserializer::new(&mut deserializer, &mut track),
+    )
+    .map_err(|e| {
+        let path = track.path().to_string();
+        if path == "." {
+            format!("Invalid {} payload: {}", descriptor.full_name(), e)
+        } else {
+            format!("Invalid {} field {}: {}", descriptor.full_name(), path, e)
+        }
+    })?;
+    deserializer
+        .end()
+        .map_err(|e| format!("Invalid JSON payload: {}", e))?;
+
+    let mut bytes = encode_message_indexes(&message_indexes(descriptor));
+    bytes.extend(message.encode_to_vec());
+
+    Ok(bytes)
+}
+
+/// The index path of a message, taken from the odd elements of its descriptor path: the
+/// position within the file's messages followed by the positions within nested messages.
+fn message_indexes(descriptor: &MessageDescriptor) -> Vec<i32> {
+    descriptor
+        .path()
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .copied()
+        .collect()
+}
+
+fn encode_message_indexes(indexes: &[i32]) -> Vec<u8> {
+    if indexes == [0] {
+        return vec![0];
+    }
+
+    let mut bytes = vec![];
+    write_varint(indexes.len() as i32, &mut bytes);
+    for index in indexes {
+        write_varint(*index, &mut bytes);
+    }
+
+    bytes
+}
+
+fn write_varint(value: i32, bytes: &mut Vec<u8>) {
+    let mut value = ((value << 1) ^ (value >> 31)) as u32;
+
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8]) -> Result<(i32, &[u8]), String> {
+    let mut value: u32 = 0;
+
+    for (position, byte) in bytes.iter().enumerate().take(5) {
+        value |= u32::from(byte & 0x7f) << (7 * position);
+        if byte & 0x80 == 0 {
+            let decoded = ((value >> 1) as i32) ^ -((value & 1) as i32);
+            return Ok((decoded, &bytes[position + 1..]));
+        }
+    }
+
+    Err("Invalid Protobuf message index".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_zigzag_varints() {
+        assert_eq!(read_varint(&[0x00]).unwrap().0, 0);
+        assert_eq!(read_varint(&[0x01]).unwrap().0, -1);
+        assert_eq!(read_varint(&[0x02]).unwrap().0, 1);
+        assert_eq!(read_varint(&[0x03]).unwrap().0, -2);
+        assert_eq!(
+            read_varint(&[0xac, 0x02, 0x7f]).unwrap(),
+            (150, &[0x7f][..])
+        );
+        assert_eq!(
+            read_varint(&[0xfe, 0xff, 0xff, 0xff, 0x0f]).unwrap().0,
+            i32::MAX
+        );
+        assert_eq!(
+            read_varint(&[0xff, 0xff, 0xff, 0xff, 0x0f]).unwrap().0,
+            i32::MIN
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_varints() {
+        assert!(read_varint(&[]).is_err());
+        assert!(read_varint(&[0x80]).is_err());
+        assert!(read_varint(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]).is_err());
+    }
+
+    #[test]
+    fn splits_message_indexes_from_payload() {
+        assert_eq!(
+            split_message_indexes(&[0x00, 0x0a, 0x01]).unwrap(),
+            (vec![0], &[0x0a, 0x01][..])
+        );
+        assert_eq!(
+            split_message_indexes(&[0x04, 0x02, 0x00, 0x0a]).unwrap(),
+            (vec![1, 0], &[0x0a][..])
+        );
+        assert_eq!(
+            split_message_indexes(&[0x02, 0x06]).unwrap(),
+            (vec![3], &[][..])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_message_indexes() {
+        assert!(split_message_indexes(&[0x01]).is_err());
+        assert!(split_message_indexes(&[0x04, 0x02]).is_err());
+        assert!(split_message_indexes(&[0xfe, 0xff, 0xff, 0xff, 0x0f]).is_err());
+    }
+
+    #[test]
+    fn writes_varints_readable_by_read_varint() {
+        for value in [0, 1, -1, 63, -64, 64, 150, 300, i32::MAX, i32::MIN] {
+            let mut bytes = vec![];
+            write_varint(value, &mut bytes);
+
+            assert_eq!(read_varint(&bytes).unwrap(), (value, &[][..]));
+        }
+    }
+
+    #[test]
+    fn encodes_message_indexes() {
+        assert_eq!(encode_message_indexes(&[0]), vec![0x00]);
+        assert_eq!(encode_message_indexes(&[1, 0]), vec![0x04, 0x02, 0x00]);
+        assert_eq!(
+            split_message_indexes(&encode_message_indexes(&[2, 5, 1])).unwrap(),
+            (vec![2, 5, 1], &[][..])
+        );
+    }
+
+    #[test]
+    fn round_trips_nested_messages() {
+        let file = compile_schema(
+            r#"
+            syntax = "proto3";
+            package test;
+
+            message First {
+              string id = 1;
+            }
+
+            message Second {
+              message Inner {
+                int32 count = 1;
+              }
+              Inner inner = 1;
+            }
+            "#,
+            BTreeMap::new(),
+        )
+        .unwrap();
+        let inner = find_message(&file, Some("test.Second.Inner")).unwrap();
+
+        assert_eq!(message_indexes(&inner), vec![1, 0]);
+
+        let bytes = encode_from_json(&inner, r#"{"count": 5}"#).unwrap();
+        assert_eq!(&bytes[..3], &[0x04, 0x02, 0x00]);
+        assert_eq!(
+            decode_to_json(&file, &bytes).unwrap(),
+            serde_json::json!({ "count": 5 })
+        );
+    }
+
+    #[test]
+    fn reports_the_failing_field_path() {
+        let file = compile_schema(
+            r#"
+            syntax = "proto3";
+            message Order {
+              message Line {
+                int32 quantity = 1;
+              }
+              repeated Line lines = 1;
+            }
+            "#,
+            BTreeMap::new(),
+        )
+        .unwrap();
+        let order = find_message(&file, None).unwrap();
+
+        let error = encode_from_json(&order, r#"{"lines": [{"quantity": "many"}]}"#).unwrap_err();
+        assert!(error.contains("lines[0].quantity"), "{}", error);
+    }
+}
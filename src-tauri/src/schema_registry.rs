@@ -1,93 +1,596 @@
-use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use schema_registry_converter::async_impl::schema_registry::{
     get_all_subjects, get_schema_by_subject, SrSettings,
 };
+use schema_registry_converter::error::SRCError;
 use schema_registry_converter::schema_registry_common::SubjectNameStrategy::RecordNameStrategy;
-use tokio::sync::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sr_reqwest::{Certificate, Client, Identity, Method};
 use tokio::time::Duration;
 
+use crate::credential_vault::ConnectionSecrets;
+use crate::kafka_connection::{non_empty, KafkaConnection};
+
 pub struct SchemaRegistry;
 
-pub static SCHEMA_REGISTRY_SETTINGS: Lazy<Mutex<Option<SrSettings>>> =
-    Lazy::new(|| Mutex::new(None));
+#[derive(Clone)]
+pub struct RegistryConnection {
+    pub settings: SrSettings,
+    url: String,
+    client: Client,
+    security: SchemaRegistrySecurity,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SchemaInfo {
+    pub id: u32,
+    pub subject: Option<String>,
+    pub version: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SchemaReference {
+    pub name: String,
+    pub subject: String,
+    pub version: u32,
+}
+
+#[derive(Serialize)]
+pub struct WriterSchema {
+    id: u32,
+    schema_type: String,
+    schema: String,
+    references: Vec<SchemaReference>,
+    versions: Vec<SchemaInfo>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredSchema {
+    #[serde(default)]
+    pub id: Option<u32>,
+    #[serde(default)]
+    pub version: Option<u32>,
+    pub schema: String,
+    #[serde(default)]
+    schema_type: Option<String>,
+    #[serde(default)]
+    pub references: Vec<SchemaReference>,
+}
+
+impl RegisteredSchema {
+    pub fn schema_type(&self) -> &str {
+        self.schema_type.as_deref().unwrap_or("AVRO")
+    }
+}
+
+#[derive(Deserialize)]
+struct SubjectVersionResponse {
+    subject: String,
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SchemaRegistrySecurity {
+    username: Option<String>,
+    password: Option<String>,
+    bearer_token: Option<String>,
+    headers: BTreeMap<String, String>,
+    ssl_ca_location: Option<String>,
+    ssl_certificate_location: Option<String>,
+    ssl_key_location: Option<String>,
+    ssl_disable_hostname_verification: bool,
+}
+
+impl SchemaRegistrySecurity {
+    pub fn secrets(&self) -> ConnectionSecrets {
+        ConnectionSecrets {
+            schema_registry_password: non_empty(&self.password),
+            schema_registry_token: non_empty(&self.bearer_token),
+            ..ConnectionSecrets::default()
+        }
+    }
+
+    pub fn without_secrets(&self) -> SchemaRegistrySecurity {
+        SchemaRegistrySecurity {
+            password: None,
+            bearer_token: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn apply_secrets(&mut self, secrets: &ConnectionSecrets) {
+        if non_empty(&self.password).is_none() {
+            self.password = secrets.schema_registry_password.clone();
+        }
+
+        if non_empty(&self.bearer_token).is_none() {
+            self.bearer_token = secrets.schema_registry_token.clone();
+        }
+    }
+
+    fn uses_tls(&self) -> bool {
+        non_empty(&self.ssl_ca_location).is_some()
+            || non_empty(&self.ssl_certificate_location).is_some()
+            || self.ssl_disable_hostname_verification
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_structure()?;
+
+        let files = [
+            ("Schema Registry CA bundle", &self.ssl_ca_location),
+            (
+                "Schema Registry client certificate",
+                &self.ssl_certificate_location,
+            ),
+            ("Schema Registry client key", &self.ssl_key_location),
+        ];
+
+        for (label, location) in files {
+            if let Some(location) = non_empty(location) {
+                if !Path::new(&location).is_file() {
+                    return Err(format!("{} file not found: {}", label, location));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_structure(&self) -> Result<(), String> {
+        if non_empty(&self.username).is_some() && non_empty(&self.bearer_token).is_some() {
+            return Err(
+                "Schema Registry accepts either basic authentication or a bearer token".to_string(),
+            );
+        }
+
+        if non_empty(&self.password).is_some() && non_empty(&self.username).is_none() {
+            return Err("Schema Registry username cannot be empty".to_string());
+        }
+
+        if self.headers.keys().any(|name| name.trim().is_empty()) {
+            return Err("Schema Registry header name cannot be empty".to_string());
+        }
+
+        match (
+            non_empty(&self.ssl_certificate_location),
+            non_empty(&self.ssl_key_location),
+        ) {
+            (Some(_), None) => {
+                Err("Schema Registry client key is required with client certificate".to_string())
+            }
+            (None, Some(_)) => {
+                Err("Schema Registry client certificate is required with client key".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn build_client(&self) -> Result<Client, String> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .danger_accept_invalid_hostnames(self.ssl_disable_hostname_verification);
+
+        if let Some(location) = non_empty(&self.ssl_ca_location) {
+            let pem = std::fs::read(&location)
+                .map_err(|e| format!("Could not read {}: {}", location, e))?;
+            let certificate = Certificate::from_pem(&pem)
+                .map_err(|e| format!("Invalid Schema Registry CA bundle: {}", e))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        if let (Some(certificate_location), Some(key_location)) = (
+            non_empty(&self.ssl_certificate_location),
+            non_empty(&self.ssl_key_location),
+        ) {
+            let certificate = std::fs::read(&certificate_location)
+                .map_err(|e| format!("Could not read {}: {}", certificate_location, e))?;
+            let key = std::fs::read(&key_location)
+                .map_err(|e| format!("Could not read {}: {}", key_location, e))?;
+            let identity = Identity::from_pkcs8_pem(&certificate, &key)
+                .map_err(|e| format!("Invalid Schema Registry client certificate: {}", e))?;
+            builder = builder.identity(identity);
+        }
+
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+impl RegistryConnection {
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.request(Method::GET, path, None).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<T, String> {
+        let mut request = self.client.request(
+            method,
+            format!("{}{}", self.url.trim_end_matches('/'), path),
+        );
+
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/vnd.schemaregistry.v1+json")
+                .body(body);
+        }
+
+        if let Some(username) = non_empty(&self.security.username) {
+            request = request.basic_auth(username, non_empty(&self.security.password));
+        }
+
+        if let Some(token) = non_empty(&self.security.bearer_token) {
+            request = request.bearer_auth(token);
+        }
+
+        for (name, value) in &self.security.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Schema Registry request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!(
+                "Schema Registry returned {} for {}: {}",
+                status, path, body
+            ));
+        }
+
+        serde_json::from_str(&body).map_err(|e| e.to_string())
+    }
+
+    pub async fn schema_versions(&self, id: u32) -> Result<Vec<SchemaInfo>, String> {
+        let versions: Vec<SubjectVersionResponse> =
+            self.get(&format!("/schemas/ids/{}/versions", id)).await?;
+
+        Ok(versions
+            .into_iter()
+            .map(|version| SchemaInfo {
+                id,
+                subject: Some(version.subject),
+                version: Some(version.version),
+            })
+            .collect())
+    }
+
+    pub async fn registered_schema(&self, id: u32) -> Result<RegisteredSchema, String> {
+        self.get(&format!("/schemas/ids/{}", id)).await
+    }
+
+    pub async fn latest_schema(&self, subject: &str) -> Result<(u32, RegisteredSchema), String> {
+        let registered: RegisteredSchema = self
+            .get(&format!(
+                "/subjects/{}/versions/latest",
+                encode_path_segment(subject)
+            ))
+            .await?;
+
+        match registered.id {
+            Some(id) => Ok((id, registered)),
+            None => Err(format!("Schema Registry returned no id for {}", subject)),
+        }
+    }
+
+    /// Looks up the id and version a schema is registered under in a subject without
+    /// registering it. The references must match the registered ones for the lookup to succeed.
+    pub async fn lookup_schema(
+        &self,
+        subject: &str,
+        schema_type: &str,
+        schema: &str,
+        references: &[SchemaReference],
+    ) -> Result<SchemaInfo, String> {
+        let body = serde_json::json!({
+            "schemaType": schema_type,
+            "schema": schema,
+            "references": references,
+        })
+        .to_string();
+        let registered: RegisteredSchema = self
+            .request(
+                Method::POST,
+                &format!("/subjects/{}", encode_path_segment(subject)),
+                Some(body),
+            )
+            .await
+            .map_err(|e| format!("Schema is not registered under subject {}: {}", subject, e))?;
+
+        let id = registered
+            .id
+            .ok_or_else(|| format!("Schema Registry returned no id for {}", subject))?;
+
+        Ok(SchemaInfo {
+            id,
+            subject: Some(subject.to_string()),
+            version: registered.version,
+        })
+    }
+
+    /// Fetches the sources of all schemas referenced directly or transitively, keyed by the
+    /// name they are imported under.
+    pub async fn resolve_references(
+        &self,
+        references: &[SchemaReference],
+    ) -> Result<BTreeMap<String, String>, String> {
+        let mut sources = BTreeMap::new();
+        let mut pending = references.to_vec();
+
+        while let Some(reference) = pending.pop() {
+            if sources.contains_key(&reference.name) {
+                continue;
+            }
+
+            let registered: RegisteredSchema = self
+                .get(&format!(
+                    "/subjects/{}/versions/{}",
+                    encode_path_segment(&reference.subject),
+                    reference.version
+                ))
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Could not resolve schema reference {}: {}",
+                        reference.name, e
+                    )
+                })?;
+            pending.extend(registered.references);
+            sources.insert(reference.name, registered.schema);
+        }
+
+        Ok(sources)
+    }
+
+    pub async fn writer_schema(&self, id: u32) -> Result<WriterSchema, String> {
+        let registered = self.registered_schema(id).await?;
+
+        Ok(WriterSchema {
+            id,
+            schema_type: registered.schema_type().to_string(),
+            schema: registered.schema,
+            references: registered.references,
+            versions: self.schema_versions(id).await?,
+        })
+    }
+}
+
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Picks the subject a topic would use under the topic name strategy, then one following the
+/// topic record name strategy (`<topic>-<record>`), then one following the record name
+/// strategy. Subjects of other topics, or of the other side of this one, are never picked.
+pub fn pick_schema_version(
+    versions: &[SchemaInfo],
+    topic: &str,
+    is_key: bool,
+) -> Option<SchemaInfo> {
+    let topic_subject = format!("{}-{}", topic, if is_key { "key" } else { "value" });
+    let topic_prefix = format!("{}-", topic);
+    let subjects = || {
+        versions
+            .iter()
+            .filter_map(|version| version.subject.as_deref().map(|subject| (version, subject)))
+    };
+
+    subjects()
+        .find(|(_, subject)| *subject == topic_subject)
+        .or_else(|| {
+            subjects().find(|(_, subject)| {
+                subject
+                    .strip_prefix(&topic_prefix)
+                    .map_or(false, is_record_name)
+            })
+        })
+        .or_else(|| subjects().find(|(_, subject)| is_record_name(subject)))
+        .map(|(version, _)| version.clone())
+}
+
+/// Record names are dotted identifiers, so they never contain the dash that separates a topic
+/// from `key`, `value` or a record name.
+fn is_record_name(subject: &str) -> bool {
+    !subject.is_empty()
+        && subject != "key"
+        && subject != "value"
+        && subject
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
 
 impl SchemaRegistry {
-    pub async fn connect(url: &str) -> Result<bool, String> {
+    pub async fn connect(
+        url: &str,
+        security: &SchemaRegistrySecurity,
+    ) -> Result<Option<RegistryConnection>, String> {
         let url = if url.is_empty() {
-            return Ok(false);
+            return Ok(None);
         } else {
             url.to_string()
         };
+        security.validate()?;
         println!("Connecting to Schema Registry: {}", url);
 
-        let sr_settings = SrSettings::new_builder(String::from(url))
-            .set_timeout(Duration::from_secs(5))
-            .build()
-            .map_err(|e| e.to_string())?;
-
-        if let Ok(_) = get_all_subjects(&sr_settings).await {
-            println!("Connected to Schema Registry");
-            let mut sr_guard = SchemaRegistry::get_settings().lock().await;
-            match &*sr_guard {
-                Some(_) => {}
-                None => {
-                    *sr_guard = Some(sr_settings);
-                }
-            }
-        } else {
-            return Err("Could not connect to Schema Registry".to_string());
+        let mut builder = SrSettings::new_builder(url.clone());
+        builder.set_timeout(Duration::from_secs(5));
+
+        if let Some(username) = non_empty(&security.username) {
+            builder.set_basic_authorization(&username, non_empty(&security.password).as_deref());
         }
 
-        Ok(true)
-    }
+        if let Some(token) = non_empty(&security.bearer_token) {
+            builder.set_token_authorization(&token);
+        }
 
-    pub async fn get_all_subjects() -> Result<Vec<String>, String> {
-        let sr_guard = SchemaRegistry::get_settings().lock().await;
+        for (name, value) in &security.headers {
+            builder.add_header(name, value);
+        }
 
-        if let Some(sr_settings) = &*sr_guard {
-            let subjects = get_all_subjects(sr_settings)
-                .await
-                .map_err(|e| e.to_string());
-            subjects
+        let client = security.build_client()?;
+        let sr_settings = if security.uses_tls() {
+            builder.build_with_client(client.clone())
         } else {
-            return Err("Schema Registry not connected".to_string());
+            builder.build().map_err(|e| e.to_string())?
+        };
+
+        match get_all_subjects(&sr_settings).await {
+            Ok(_) => println!("Connected to Schema Registry"),
+            Err(e) => return Err(describe_failure(e)),
         }
+
+        Ok(Some(RegistryConnection {
+            settings: sr_settings,
+            url,
+            client,
+            security: security.clone(),
+        }))
+    }
+
+    pub async fn get_all_subjects(connection_id: &str) -> Result<Vec<String>, String> {
+        let sr_settings = SchemaRegistry::get_settings(connection_id).await?;
+
+        get_all_subjects(&sr_settings)
+            .await
+            .map_err(|e| e.to_string())
     }
 
-    pub async fn get_schema(schema_name: &str) -> Result<String, String> {
+    pub async fn get_schema(connection_id: &str, schema_name: &str) -> Result<String, String> {
         let strategy = RecordNameStrategy(schema_name.to_string());
-        let sr_guard = SchemaRegistry::get_settings().lock().await;
-        return if let Some(sr_settings) = &*sr_guard {
-            let subject = get_schema_by_subject(sr_settings, &strategy)
-                .await
-                .map_err(|e| e.to_string());
-            if let Ok(subject) = subject {
-                Ok(subject.schema)
-            } else {
-                Err("Could not get schema".to_string())
-            }
+        let sr_settings = SchemaRegistry::get_settings(connection_id).await?;
+
+        let subject = get_schema_by_subject(&sr_settings, &strategy)
+            .await
+            .map_err(|e| e.to_string());
+        if let Ok(subject) = subject {
+            Ok(subject.schema)
         } else {
-            Err("Schema Registry not connected".to_string())
-        };
+            Err("Could not get schema".to_string())
+        }
     }
 
-    pub async fn disconnect() -> Result<(), String> {
-        let mut sr_guard = SchemaRegistry::get_settings().lock().await;
-        *sr_guard = None;
-        Ok(())
+    pub async fn get_settings(connection_id: &str) -> Result<SrSettings, String> {
+        Ok(SchemaRegistry::get_connection(connection_id)
+            .await?
+            .settings)
     }
 
-    pub fn get_settings() -> &'static Mutex<Option<SrSettings>> {
-        &SCHEMA_REGISTRY_SETTINGS
+    pub async fn get_connection(connection_id: &str) -> Result<RegistryConnection, String> {
+        let connection = KafkaConnection::get(connection_id).await?;
+
+        match &connection.schema_registry {
+            Some(registry) => Ok(registry.clone()),
+            None => Err("Schema Registry not connected".to_string()),
+        }
+    }
+}
+
+fn describe_failure(error: SRCError) -> String {
+    let error = error.to_string();
+
+    if error.contains("401") || error.contains("403") {
+        format!(
+            "Authentication with Schema Registry failed: {}. Check credentials and headers",
+            error
+        )
+    } else if error.contains("certificate") || error.contains("tls") || error.contains("TLS") {
+        format!(
+            "TLS handshake with Schema Registry failed: {}. Check CA bundle, client certificate and key",
+            error
+        )
+    } else {
+        format!("Could not connect to Schema Registry: {}", error)
     }
 }
 
 #[tauri::command]
-pub async fn fetch_sr_subjects() -> Result<Vec<String>, String> {
-    SchemaRegistry::get_all_subjects().await
+pub async fn fetch_sr_subjects(connection_id: &str) -> Result<Vec<String>, String> {
+    SchemaRegistry::get_all_subjects(connection_id).await
 }
 
 #[tauri::command]
-pub async fn fetch_schema(subject: &str) -> Result<String, String> {
-    SchemaRegistry::get_schema(subject).await
+pub async fn fetch_schema(connection_id: &str, subject: &str) -> Result<String, String> {
+    SchemaRegistry::get_schema(connection_id, subject).await
+}
+
+#[tauri::command]
+pub async fn fetch_writer_schema(
+    connection_id: &str,
+    schema_id: u32,
+) -> Result<WriterSchema, String> {
+    SchemaRegistry::get_connection(connection_id)
+        .await?
+        .writer_schema(schema_id)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(subject: &str) -> SchemaInfo {
+        SchemaInfo {
+            id: 1,
+            subject: Some(subject.to_string()),
+            version: Some(1),
+        }
+    }
+
+    fn picked(subjects: &[&str], topic: &str, is_key: bool) -> Option<String> {
+        let versions: Vec<SchemaInfo> = subjects.iter().map(|subject| version(subject)).collect();
+
+        pick_schema_version(&versions, topic, is_key).and_then(|version| version.subject)
+    }
+
+    #[test]
+    fn prefers_topic_name_strategy_subject() {
+        let subjects = ["com.acme.Order", "orders-key", "orders-value"];
+
+        assert_eq!(
+            picked(&subjects, "orders", false).as_deref(),
+            Some("orders-value")
+        );
+        assert_eq!(
+            picked(&subjects, "orders", true).as_deref(),
+            Some("orders-key")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_topic_record_then_record_name_strategy() {
+        let subjects = ["com.acme.Order", "orders-com.acme.Order"];
+
+        assert_eq!(
+            picked(&subjects, "orders", false).as_deref(),
+            Some("orders-com.acme.Order")
+        );
+        assert_eq!(
+            picked(&subjects, "payments", false).as_deref(),
+            Some("com.acme.Order")
+        );
+    }
+
+    #[test]
+    fn ignores_other_topics_and_the_other_side() {
+        assert_eq!(picked(&["orders-value"], "orders", true), None);
+        assert_eq!(picked(&["orders-archive-value"], "orders", false), None);
+    }
 }
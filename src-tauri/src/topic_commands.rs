@@ -47,13 +47,9 @@ pub struct TopicPageResult {
 }
 
 #[tauri::command]
-pub async fn fetch_topics(filter: &str) -> Result<Vec<TopicResult>, String> {
-    let kafka = KafkaConnection::get_consumer_instance().lock().await;
-
-    let consumer = match &*kafka {
-        Some(consumer_instance) => consumer_instance,
-        None => return Err("Kafka connection not established".to_string()),
-    };
+pub async fn fetch_topics(connection_id: &str, filter: &str) -> Result<Vec<TopicResult>, String> {
+    let connection = KafkaConnection::get(connection_id).await?;
+    let consumer = &connection.consumer;
 
     let metadata = match consumer.fetch_metadata(None, Duration::from_secs(10)) {
         Ok(metadata) => metadata,
@@ -104,7 +100,7 @@ pub async fn fetch_topics(filter: &str) -> Result<Vec<TopicResult>, String> {
         return Ok(topics_info);
     }
 
-    let inner_consumer = create_consumer().await?;
+    let inner_consumer = create_consumer(connection_id).await?;
 
     let start_offsets = fetch_offsets(&inner_consumer, start_assignment)?;
     let end_offsets = fetch_offsets(&inner_consumer, end_assignment)?;
@@ -134,8 +130,8 @@ pub async fn fetch_topics(filter: &str) -> Result<Vec<TopicResult>, String> {
     Ok(topics_info)
 }
 
-async fn create_consumer() -> Result<BaseConsumer, String> {
-    let client_config = KafkaConnection::get_client_config()
+async fn create_consumer(connection_id: &str) -> Result<BaseConsumer, String> {
+    let client_config = KafkaConnection::get_client_config(connection_id)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -143,147 +139,138 @@ async fn create_consumer() -> Result<BaseConsumer, String> {
 }
 
 #[tauri::command]
-pub async fn fetch_topic(name: &str) -> Result<TopicPageResult, String> {
-    let kafka = KafkaConnection::get_consumer_instance().lock().await;
-    if let Some(consumer) = &*kafka {
-        let metadata = consumer
-            .fetch_metadata(Some(name), Duration::from_secs(10))
-            .unwrap();
-
-        let mut topic_info = TopicPageResult {
-            name: name.to_owned(),
-            partitions: vec![],
-        };
-
-        for topic in metadata.topics().iter() {
-            let topic_name = topic.name();
-
-            let mut end_assignment = TopicPartitionList::new();
-            let mut start_assignment = TopicPartitionList::new();
-            for partition_metadata in topic.partitions().iter() {
-                fulfill_tpl(
-                    &mut start_assignment,
-                    topic_name,
-                    partition_metadata.id(),
-                    Offset::Beginning,
-                )?;
-                fulfill_tpl(
-                    &mut end_assignment,
-                    topic_name,
-                    partition_metadata.id(),
-                    Offset::End,
-                )?;
-                let partition = Partition {
-                    id: partition_metadata.id(),
-                    leader: partition_metadata.leader(),
-                    replicas: partition_metadata.replicas().to_vec(),
-                    low: 0,
-                    high: 0,
-                    messages: 0,
-                };
-
-                topic_info.partitions.push(partition);
-            }
-            let start_offsets = fetch_offsets(&consumer, start_assignment)?;
-            let end_offsets = fetch_offsets(&consumer, end_assignment)?;
-
-            for partition in topic_info.partitions.iter_mut() {
-                let partition_start_offsets = start_offsets.elements_for_topic(&topic_name);
-                let partition_end_offsets = end_offsets.elements_for_topic(&topic_name);
-
-                for partition_elem in partition_end_offsets.iter() {
-                    if partition_elem.partition() == partition.id {
-                        match partition_elem.offset() {
-                            Offset::Offset(offset_num) => {
-                                partition.messages += offset_num;
-                                partition.high = offset_num;
-                            }
-                            _ => {}
+pub async fn fetch_topic(connection_id: &str, name: &str) -> Result<TopicPageResult, String> {
+    let connection = KafkaConnection::get(connection_id).await?;
+    let consumer = &connection.consumer;
+    let metadata = consumer
+        .fetch_metadata(Some(name), Duration::from_secs(10))
+        .unwrap();
+
+    let mut topic_info = TopicPageResult {
+        name: name.to_owned(),
+        partitions: vec![],
+    };
+
+    for topic in metadata.topics().iter() {
+        let topic_name = topic.name();
+
+        let mut end_assignment = TopicPartitionList::new();
+        let mut start_assignment = TopicPartitionList::new();
+        for partition_metadata in topic.partitions().iter() {
+            fulfill_tpl(
+                &mut start_assignment,
+                topic_name,
+                partition_metadata.id(),
+                Offset::Beginning,
+            )?;
+            fulfill_tpl(
+                &mut end_assignment,
+                topic_name,
+                partition_metadata.id(),
+                Offset::End,
+            )?;
+            let partition = Partition {
+                id: partition_metadata.id(),
+                leader: partition_metadata.leader(),
+                replicas: partition_metadata.replicas().to_vec(),
+                low: 0,
+                high: 0,
+                messages: 0,
+            };
+
+            topic_info.partitions.push(partition);
+        }
+        let start_offsets = fetch_offsets(&consumer, start_assignment)?;
+        let end_offsets = fetch_offsets(&consumer, end_assignment)?;
+
+        for partition in topic_info.partitions.iter_mut() {
+            let partition_start_offsets = start_offsets.elements_for_topic(&topic_name);
+            let partition_end_offsets = end_offsets.elements_for_topic(&topic_name);
+
+            for partition_elem in partition_end_offsets.iter() {
+                if partition_elem.partition() == partition.id {
+                    match partition_elem.offset() {
+                        Offset::Offset(offset_num) => {
+                            partition.messages += offset_num;
+                            partition.high = offset_num;
                         }
+                        _ => {}
                     }
                 }
+            }
 
-                for partition_elem in partition_start_offsets.iter() {
-                    if partition_elem.partition() == partition.id {
-                        match partition_elem.offset() {
-                            Offset::Offset(offset_num) => {
-                                partition.messages -= offset_num;
-                                partition.low = offset_num;
-                            }
-                            _ => {}
+            for partition_elem in partition_start_offsets.iter() {
+                if partition_elem.partition() == partition.id {
+                    match partition_elem.offset() {
+                        Offset::Offset(offset_num) => {
+                            partition.messages -= offset_num;
+                            partition.low = offset_num;
                         }
+                        _ => {}
                     }
                 }
             }
         }
-        Ok(topic_info)
-    } else {
-        Err("Kafka connection not established".to_string())
     }
+    Ok(topic_info)
 }
 
 #[tauri::command]
-pub async fn drop_topics(topic_names: Vec<&str>) -> Result<(), String> {
-    let admin_client = KafkaConnection::get_admin_client_instance().lock().await;
-
-    if let Some(admin_client) = &*admin_client {
-        let opts = AdminOptions::new();
+pub async fn drop_topics(connection_id: &str, topic_names: Vec<&str>) -> Result<(), String> {
+    let connection = KafkaConnection::get(connection_id).await?;
+    let opts = AdminOptions::new();
 
-        admin_client
-            .delete_topics(&topic_names, &opts)
-            .await
-            .map_err(|err| err.to_string())?;
+    connection
+        .admin_client
+        .delete_topics(&topic_names, &opts)
+        .await
+        .map_err(|err| err.to_string())?;
 
-        return Ok(());
-    }
-    Err("Kafka connection not established".to_string())
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn create_topic<'a>(topic: Topic<'a>) -> Result<(), String> {
-    let admin_client = KafkaConnection::get_admin_client_instance().lock().await;
-
-    if let Some(admin_client) = &*admin_client {
-        let opts = AdminOptions::new();
-
-        let retention_time_str = topic.retention_time.to_string();
-        let insync_replicas = topic.insync_replicas.to_string();
-        let max_message_bytes = if topic.size_limit > 0 {
-            topic.size_limit.to_string()
-        } else {
-            "1048588".to_string()
-        };
-        let cleanup_policy = topic.cleanup_policy.to_ascii_lowercase();
-        let new_topic = NewTopic::new(
-            topic.name,
-            topic.partitions,
-            TopicReplication::Fixed(topic.replication_factor),
-        )
-        .set("retention.ms", &retention_time_str)
-        .set("cleanup.policy", &cleanup_policy)
-        .set("min.insync.replicas", &insync_replicas)
-        .set("max.message.bytes", &max_message_bytes);
-
-        let result = admin_client
-            .create_topics(&[new_topic], &opts)
-            .await
-            .map_err(|err| err.to_string())?;
-
-        match result.len() {
-            1 => match &result[0] {
-                Ok(_) => {
-                    println!("Topic {} created", topic.name);
-                }
-                Err(e) => {
-                    return Err(e.1.to_string());
-                }
-            },
-            _ => {
-                return Err("Unknown error".to_string());
+pub async fn create_topic<'a>(connection_id: &str, topic: Topic<'a>) -> Result<(), String> {
+    let connection = KafkaConnection::get(connection_id).await?;
+    let admin_client = &connection.admin_client;
+    let opts = AdminOptions::new();
+
+    let retention_time_str = topic.retention_time.to_string();
+    let insync_replicas = topic.insync_replicas.to_string();
+    let max_message_bytes = if topic.size_limit > 0 {
+        topic.size_limit.to_string()
+    } else {
+        "1048588".to_string()
+    };
+    let cleanup_policy = topic.cleanup_policy.to_ascii_lowercase();
+    let new_topic = NewTopic::new(
+        topic.name,
+        topic.partitions,
+        TopicReplication::Fixed(topic.replication_factor),
+    )
+    .set("retention.ms", &retention_time_str)
+    .set("cleanup.policy", &cleanup_policy)
+    .set("min.insync.replicas", &insync_replicas)
+    .set("max.message.bytes", &max_message_bytes);
+
+    let result = admin_client
+        .create_topics(&[new_topic], &opts)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    match result.len() {
+        1 => match &result[0] {
+            Ok(_) => {
+                println!("Topic {} created", topic.name);
             }
+            Err(e) => {
+                return Err(e.1.to_string());
+            }
+        },
+        _ => {
+            return Err("Unknown error".to_string());
         }
-
-        return Ok(());
     }
-    Err("Kafka connection not established".to_string())
+
+    Ok(())
 }
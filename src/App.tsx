@@ -1,7 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { ResizableHandle, ResizablePanel, ResizablePanelGroup } from '@/components/ui/resizable';
-import { Connect } from '@/components/pages/Connect';
+import { Connect, IKafkaSecurity, ISchemaRegistrySecurity } from '@/components/pages/Connect';
 import { Button } from '@/components/ui/button';
 import { Topics } from '@/components/pages/Topics';
 import { Route, Routes, useNavigate } from 'react-router-dom';
@@ -21,16 +21,24 @@ function App() {
     invoke('check_version').catch(console.error);
   }, []);
 
-  function connect(payload: { host: string; name: string; schemaRegistry?: string }) {
+  function connect(payload: {
+    host: string;
+    name: string;
+    schemaRegistry?: string;
+    security?: IKafkaSecurity;
+    schemaRegistrySecurity?: ISchemaRegistrySecurity;
+    properties?: Record<string, string>;
+    savedConnectionId?: string;
+  }) {
     (async () => {
       invoke('check_version').catch(console.error);
       setError('');
       try {
         setConnecting(true);
-        const result = await invoke<boolean>('connect', payload);
+        const connectionId = await invoke<string>('connect', payload);
         navigate('/');
-        setIsConnected(result);
-        setSettings({ ...settings, schemaRegistryConnected: !!payload.schemaRegistry });
+        setIsConnected(true);
+        setSettings({ ...settings, connectionId, schemaRegistryConnected: !!payload.schemaRegistry });
       } catch (err: any) {
         setError(err);
       } finally {
@@ -43,8 +51,9 @@ function App() {
     (async () => {
       setError('');
       try {
-        await invoke<boolean>('disconnect');
+        await invoke('disconnect', { connectionId: settings.connectionId });
         setIsConnected(false);
+        setSettings({ ...settings, connectionId: '' });
         navigate('/');
       } catch (err: any) {
         setError(err);
@@ -52,6 +61,10 @@ function App() {
     })();
   }
 
+  function openWindow() {
+    invoke('open_window').catch(err => setError(err));
+  }
+
   if (!isConnected) {
     return <Connect onConnect={connect} error={error} connecting={connecting} />;
   }
@@ -85,9 +98,14 @@ function App() {
                 </Button>
               )}
             </div>
-            <Button variant="destructive" className="m-2 ml-0 w-full" onClick={disconnect}>
-              Disconnect
-            </Button>
+            <div className="w-full">
+              <Button variant="outline" className="m-2 ml-0 w-full" onClick={openWindow}>
+                New window
+              </Button>
+              <Button variant="destructive" className="m-2 ml-0 w-full" onClick={disconnect}>
+                Disconnect
+              </Button>
+            </div>
           </div>
         </ResizablePanel>
         <ResizableHandle withHandle />
@@ -2,6 +2,7 @@ import { createContext, ReactNode, useContext, useState } from 'react';
 
 export interface ISettings {
   schemaRegistryConnected: boolean;
+  connectionId: string;
 }
 
 export interface ISettingsContextValue {
@@ -10,14 +11,14 @@ export interface ISettingsContextValue {
 }
 
 const initialState = {
-  settings: { schemaRegistryConnected: false },
+  settings: { schemaRegistryConnected: false, connectionId: '' },
   setSettings: () => null,
 };
 
 const SettingsProviderContext = createContext<ISettingsContextValue>(initialState);
 
 export function SettingsProvider({ children }: { children: ReactNode }) {
-  const [settings, setSettings] = useState<ISettings>({ schemaRegistryConnected: false });
+  const [settings, setSettings] = useState<ISettings>({ schemaRegistryConnected: false, connectionId: '' });
 
   const value = {
     settings,
@@ -1,17 +1,110 @@
 import { Input } from '@/components/ui/input';
-import { useEffect, useState } from 'react';
+import { ChangeEvent, useEffect, useState } from 'react';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert';
-import { AlertCircle } from 'lucide-react';
+import { AlertCircle, ArrowDown, ArrowUp } from 'lucide-react';
 import { Card, CardContent, CardFooter, CardHeader, CardTitle } from '@/components/ui/card.tsx';
 import { invoke } from '@tauri-apps/api/tauri';
 import { Label } from '@/components/ui/label.tsx';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select.tsx';
+import { Checkbox } from '@/components/ui/checkbox.tsx';
+import { Textarea } from '@/components/ui/textarea';
 import { toast } from 'sonner';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog.tsx';
+
+export interface IKafkaSecurity {
+  security_protocol?: string;
+  sasl_mechanism?: string;
+  sasl_username?: string;
+  sasl_password?: string;
+  ssl_ca_location?: string;
+  ssl_certificate_location?: string;
+  ssl_key_location?: string;
+  ssl_key_password?: string;
+  ssl_disable_hostname_verification?: boolean;
+}
+
+export interface ISchemaRegistrySecurity {
+  username?: string;
+  password?: string;
+  bearer_token?: string;
+  headers?: Record<string, string>;
+  ssl_ca_location?: string;
+  ssl_certificate_location?: string;
+  ssl_key_location?: string;
+  ssl_disable_hostname_verification?: boolean;
+}
 
 interface IConnection {
+  id: string;
   kafka_broker: string;
   schema_registry?: string;
   name: string;
+  tags: string[];
+  security?: IKafkaSecurity;
+  schema_registry_security?: ISchemaRegistrySecurity;
+  properties?: Record<string, string>;
+  has_credentials: boolean;
+}
+
+const SECURITY_PROTOCOLS = ['PLAINTEXT', 'SSL', 'SASL_PLAINTEXT', 'SASL_SSL'];
+const SASL_MECHANISMS = ['PLAIN', 'SCRAM-SHA-256', 'SCRAM-SHA-512'];
+
+interface IImportedProperties {
+  security: IKafkaSecurity;
+  properties: Record<string, string>;
+  skipped: { key: string; reason: string }[];
+}
+
+function parseProperties(text: string): Record<string, string> {
+  const properties: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const trimmed = line.trim();
+    const separator = trimmed.indexOf('=');
+    if (!trimmed || trimmed.startsWith('#') || separator < 1) {
+      continue;
+    }
+    properties[trimmed.slice(0, separator).trim()] = trimmed.slice(separator + 1).trim();
+  }
+
+  return properties;
+}
+
+function serializeProperties(properties?: Record<string, string>) {
+  return Object.entries(properties || {})
+    .map(([key, value]) => `${key}=${value}`)
+    .join('\n');
+}
+
+type SavedConnectionAction = 'edit' | 'rename' | 'tags' | 'delete';
+
+const ALL_TAGS = '__all';
+
+interface IImportReport {
+  imported: IConnection[];
+  conflicts: { name: string; kafka_broker: string; reason: string }[];
+}
+
+interface IVaultStatus {
+  exists: boolean;
+  unlocked: boolean;
+}
+
+interface IConnectPayload {
+  host: string;
+  name: string;
+  schemaRegistry?: string;
+  security?: IKafkaSecurity;
+  schemaRegistrySecurity?: ISchemaRegistrySecurity;
+  properties?: Record<string, string>;
+  savedConnectionId?: string;
 }
 
 export function Connect({
@@ -26,21 +119,231 @@ export function Connect({
   const [broker, setBroker] = useState('');
   const [schemaRegistry, setSchemaRegistry] = useState('');
   const [name, setName] = useState('');
+  const [security, setSecurity] = useState<IKafkaSecurity>({ security_protocol: 'PLAINTEXT' });
+  const [schemaRegistrySecurity, setSchemaRegistrySecurity] = useState<ISchemaRegistrySecurity>({});
+  const [schemaRegistryHeaders, setSchemaRegistryHeaders] = useState('');
+  const [properties, setProperties] = useState('');
+  const [propertiesFile, setPropertiesFile] = useState('');
 
   const [knownHosts, setKnownHosts] = useState<IConnection[] | null>(null);
+  const [tags, setTags] = useState<string[]>([]);
+  const [tagFilter, setTagFilter] = useState(ALL_TAGS);
+  const [savedAction, setSavedAction] = useState<SavedConnectionAction | null>(null);
+  const [draft, setDraft] = useState<IConnection | null>(null);
+  const [draftText, setDraftText] = useState('');
+  const [profilesFile, setProfilesFile] = useState('');
+  const [exportSecrets, setExportSecrets] = useState(false);
+  const [vault, setVault] = useState<IVaultStatus>({ exists: false, unlocked: false });
+  const [pendingConnect, setPendingConnect] = useState<IConnectPayload | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [vaultError, setVaultError] = useState('');
+
+  async function loadHosts(tag: string) {
+    try {
+      const hosts = await invoke<IConnection[]>('fetch_saved_brokers');
+      setTags([...new Set(hosts.flatMap(host => host.tags))].sort());
+      setKnownHosts(tag === ALL_TAGS ? hosts : await invoke<IConnection[]>('fetch_saved_brokers', { tag }));
+    } catch (e) {
+      setKnownHosts([]);
+      toast.error('Error fetching saved brokers');
+    }
+  }
 
   useEffect(() => {
-    (async () => {
-      try {
-        const hosts = await invoke<IConnection[]>('fetch_saved_brokers');
-        setKnownHosts(hosts);
-      } catch (e) {
-        setKnownHosts([]);
-        toast.error('Error fetching saved brokers');
-      }
-    })();
+    loadHosts(tagFilter);
+  }, [tagFilter]);
+
+  useEffect(() => {
+    invoke<IVaultStatus>('vault_status')
+      .then(setVault)
+      .catch(() => toast.error('Error fetching credential vault status'));
   }, []);
 
+  function connectWithVault(payload: IConnectPayload, needsVault: boolean) {
+    if (needsVault && !vault.unlocked) {
+      setPassphrase('');
+      setVaultError('');
+      setPendingConnect(payload);
+      return;
+    }
+
+    onConnect(payload);
+  }
+
+  async function unlockAndConnect() {
+    if (!pendingConnect) {
+      return;
+    }
+
+    try {
+      await invoke('unlock_vault', { passphrase });
+      setVault({ exists: true, unlocked: true });
+      onConnect(pendingConnect);
+      setPendingConnect(null);
+    } catch (e: any) {
+      setVaultError(e);
+    }
+  }
+
+  function connectWithoutSavingCredentials() {
+    if (!pendingConnect) {
+      return;
+    }
+
+    toast.warning('The credential vault is locked, credentials of this connection will not be saved');
+    onConnect(pendingConnect);
+    setPendingConnect(null);
+  }
+
+  function openSavedAction(action: SavedConnectionAction, host: IConnection) {
+    setDraft(host);
+    setDraftText(
+      action === 'edit' ? serializeProperties(host.properties) : action === 'tags' ? host.tags.join(', ') : ''
+    );
+    setSavedAction(action);
+  }
+
+  async function applySavedAction() {
+    if (!draft) {
+      return;
+    }
+
+    try {
+      if (savedAction === 'edit') {
+        await invoke('update_saved_broker', {
+          connection: {
+            ...draft,
+            schema_registry: draft.schema_registry || '',
+            properties: parseProperties(draftText),
+          },
+        });
+      } else if (savedAction === 'rename') {
+        await invoke('rename_saved_broker', { id: draft.id, name: draft.name });
+      } else if (savedAction === 'tags') {
+        const draftTags = draftText
+          .split(',')
+          .map(tag => tag.trim())
+          .filter(Boolean);
+        await invoke('set_saved_broker_tags', { id: draft.id, tags: draftTags });
+      } else if (savedAction === 'delete') {
+        await invoke('delete_saved_broker', { id: draft.id });
+      }
+      setSavedAction(null);
+      await loadHosts(tagFilter);
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
+  async function duplicateHost(id: string) {
+    try {
+      await invoke('duplicate_saved_broker', { id });
+      await loadHosts(tagFilter);
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
+  async function moveHost(index: number, offset: number) {
+    if (!knownHosts) {
+      return;
+    }
+
+    const ids = knownHosts.map(host => host.id);
+    [ids[index], ids[index + offset]] = [ids[index + offset], ids[index]];
+    try {
+      setKnownHosts(await invoke<IConnection[]>('reorder_saved_brokers', { ids }));
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
+  async function exportProfiles() {
+    try {
+      const exported = await invoke<number>('export_connection_profiles', {
+        ids: (knownHosts || []).map(host => host.id),
+        path: profilesFile,
+        includeSecrets: exportSecrets,
+      });
+      toast.success(`Exported ${exported} connections to ${profilesFile}`);
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
+  async function importProfiles() {
+    try {
+      const report = await invoke<IImportReport>('import_connection_profiles', { path: profilesFile });
+      toast.success(`Imported ${report.imported.length} connections`);
+      report.conflicts.forEach(conflict => toast.warning(`Skipped ${conflict.name}: ${conflict.reason}`));
+      await loadHosts(tagFilter);
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
+  function changeRegistrySecurity(field: keyof ISchemaRegistrySecurity) {
+    return (e: ChangeEvent<HTMLInputElement>) =>
+      setSchemaRegistrySecurity({ ...schemaRegistrySecurity, [field]: e.target.value });
+  }
+
+  const usesSasl = security.security_protocol?.startsWith('SASL_');
+  const usesSsl = security.security_protocol?.endsWith('SSL');
+
+  function connectNew() {
+    const payloadSecurity: IKafkaSecurity = { security_protocol: security.security_protocol };
+    if (usesSasl) {
+      payloadSecurity.sasl_mechanism = security.sasl_mechanism || 'PLAIN';
+      payloadSecurity.sasl_username = security.sasl_username;
+      payloadSecurity.sasl_password = security.sasl_password;
+    }
+    if (usesSsl) {
+      payloadSecurity.ssl_ca_location = security.ssl_ca_location;
+      payloadSecurity.ssl_certificate_location = security.ssl_certificate_location;
+      payloadSecurity.ssl_key_location = security.ssl_key_location;
+      payloadSecurity.ssl_key_password = security.ssl_key_password;
+      payloadSecurity.ssl_disable_hostname_verification = security.ssl_disable_hostname_verification;
+    }
+
+    const headers: Record<string, string> = {};
+    for (const line of schemaRegistryHeaders.split('\n')) {
+      const separator = line.indexOf(':');
+      if (separator > 0) {
+        headers[line.slice(0, separator).trim()] = line.slice(separator + 1).trim();
+      }
+    }
+    const payloadRegistrySecurity = schemaRegistry ? { ...schemaRegistrySecurity, headers } : {};
+
+    connectWithVault(
+      {
+        host: broker,
+        name,
+        schemaRegistry,
+        security: payloadSecurity,
+        schemaRegistrySecurity: payloadRegistrySecurity,
+        properties: parseProperties(properties),
+      },
+      !!payloadSecurity.sasl_password ||
+        !!payloadSecurity.ssl_key_password ||
+        !!payloadRegistrySecurity.password ||
+        !!payloadRegistrySecurity.bearer_token
+    );
+  }
+
+  async function importPropertiesFile() {
+    try {
+      const imported = await invoke<IImportedProperties>('import_client_properties', { path: propertiesFile });
+      setSecurity({
+        ...imported.security,
+        security_protocol: imported.security.security_protocol?.toUpperCase() || 'PLAINTEXT',
+      });
+      setProperties(serializeProperties(imported.properties));
+      imported.skipped.forEach(property => toast.warning(`Skipped ${property.key}: ${property.reason}`));
+    } catch (e: any) {
+      toast.error(e);
+    }
+  }
+
   if (!knownHosts) {
     return <div className="flex flex-row flex-wrap items-start w-screen pt-6">Loading...</div>;
   }
@@ -48,10 +351,49 @@ export function Connect({
   return (
     <>
       <div className="flex flex-row flex-wrap items-start w-screen pt-6">
-        {knownHosts.map(host => (
-          <Card key={host.kafka_broker + host.schema_registry + host.name} className="w-[400px] ml-6 mt-10">
+        <div className="flex w-screen items-center space-x-2 px-6">
+          <Input
+            className="w-96"
+            placeholder="/path/to/connections.json or .yaml"
+            value={profilesFile}
+            onChange={e => setProfilesFile(e.target.value)}
+          />
+          <Button variant="outline" disabled={!profilesFile || !knownHosts.length} onClick={exportProfiles}>
+            Export
+          </Button>
+          <Button variant="outline" disabled={!profilesFile} onClick={importProfiles}>
+            Import
+          </Button>
+          <Checkbox
+            id="exportSecrets"
+            checked={exportSecrets}
+            onCheckedChange={checked => setExportSecrets(checked === true)}
+          />
+          <label htmlFor="exportSecrets" className="cursor-pointer text-sm font-medium leading-none">
+            Include credentials in export
+          </label>
+          {tags.length > 0 && (
+            <Select value={tagFilter} onValueChange={setTagFilter}>
+              <SelectTrigger className="w-64">
+                <span className="mr-2 text-muted-foreground">Tag:</span>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={ALL_TAGS}>All connections</SelectItem>
+                {tags.map(tag => (
+                  <SelectItem key={tag} value={tag}>
+                    {tag}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          )}
+        </div>
+        {knownHosts.map((host, i) => (
+          <Card key={host.id} className="w-[400px] ml-6 mt-10">
             <CardHeader>
               <CardTitle>{host.name}</CardTitle>
+              {host.tags.length > 0 && <p className="text-sm text-muted-foreground">{host.tags.join(', ')}</p>}
             </CardHeader>
             <CardContent className="grid gap-4">
               <div className="flex items-center space-x-4 rounded-md">
@@ -65,16 +407,62 @@ export function Connect({
                 </div>
               }
             </CardContent>
-            <CardFooter>
+            <CardFooter className="flex-col gap-2">
               <Button
                 className="w-full"
                 disabled={connecting}
                 onClick={() =>
-                  onConnect({ host: host.kafka_broker, name: host.name, schemaRegistry: host.schema_registry })
+                  connectWithVault(
+                    {
+                      host: host.kafka_broker,
+                      name: host.name,
+                      schemaRegistry: host.schema_registry,
+                      security: host.security,
+                      schemaRegistrySecurity: host.schema_registry_security,
+                      properties: host.properties,
+                      savedConnectionId: host.id,
+                    },
+                    host.has_credentials
+                  )
                 }
               >
                 Connect
               </Button>
+              <div className="flex w-full flex-wrap gap-2">
+                <Button variant="outline" size="sm" onClick={() => openSavedAction('edit', host)}>
+                  Edit
+                </Button>
+                <Button variant="outline" size="sm" onClick={() => openSavedAction('rename', host)}>
+                  Rename
+                </Button>
+                <Button variant="outline" size="sm" onClick={() => openSavedAction('tags', host)}>
+                  Tags
+                </Button>
+                <Button variant="outline" size="sm" onClick={() => duplicateHost(host.id)}>
+                  Duplicate
+                </Button>
+                <Button variant="outline" size="sm" onClick={() => openSavedAction('delete', host)}>
+                  Delete
+                </Button>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  title="Move up"
+                  disabled={tagFilter !== ALL_TAGS || i === 0}
+                  onClick={() => moveHost(i, -1)}
+                >
+                  <ArrowUp className="h-4 w-4" />
+                </Button>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  title="Move down"
+                  disabled={tagFilter !== ALL_TAGS || i === knownHosts.length - 1}
+                  onClick={() => moveHost(i, 1)}
+                >
+                  <ArrowDown className="h-4 w-4" />
+                </Button>
+              </div>
             </CardFooter>
           </Card>
         ))}
@@ -93,15 +481,182 @@ export function Connect({
                 onChange={e => setSchemaRegistry(e.target.value)}
               />
             </div>
+            {schemaRegistry && (
+              <>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryUsername">Schema Registry username (optional)</Label>
+                  <Input id="registryUsername" onChange={changeRegistrySecurity('username')} />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryPassword">Schema Registry password (optional)</Label>
+                  <Input id="registryPassword" type="password" onChange={changeRegistrySecurity('password')} />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryToken">Schema Registry bearer token (optional)</Label>
+                  <Input id="registryToken" type="password" onChange={changeRegistrySecurity('bearer_token')} />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryCaLocation">Schema Registry CA bundle (optional)</Label>
+                  <Input id="registryCaLocation" onChange={changeRegistrySecurity('ssl_ca_location')} />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryCertificateLocation">Schema Registry client certificate (optional)</Label>
+                  <Input
+                    id="registryCertificateLocation"
+                    onChange={changeRegistrySecurity('ssl_certificate_location')}
+                  />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryKeyLocation">Schema Registry client key (optional)</Label>
+                  <Input id="registryKeyLocation" onChange={changeRegistrySecurity('ssl_key_location')} />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="registryHeaders">Schema Registry headers (optional)</Label>
+                  <Textarea
+                    id="registryHeaders"
+                    placeholder="X-Tenant: team-a"
+                    value={schemaRegistryHeaders}
+                    onChange={e => setSchemaRegistryHeaders(e.target.value)}
+                  />
+                </div>
+              </>
+            )}
             <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
               <Label htmlFor="brokers">Kafka brokers</Label>
               <Input id="brokers" placeholder="kafka:9092" onChange={e => setBroker(e.target.value)} />
             </div>
-            <Button
-              className="m-6"
-              disabled={connecting}
-              onClick={() => onConnect({ host: broker, name, schemaRegistry })}
-            >
+            <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+              <Label htmlFor="securityProtocol">Security protocol</Label>
+              <Select
+                value={security.security_protocol}
+                onValueChange={value => setSecurity({ ...security, security_protocol: value })}
+              >
+                <SelectTrigger id="securityProtocol">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {SECURITY_PROTOCOLS.map(protocol => (
+                    <SelectItem key={protocol} value={protocol}>
+                      {protocol}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            {usesSasl && (
+              <>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="saslMechanism">SASL mechanism</Label>
+                  <Select
+                    value={security.sasl_mechanism || 'PLAIN'}
+                    onValueChange={value => setSecurity({ ...security, sasl_mechanism: value })}
+                  >
+                    <SelectTrigger id="saslMechanism">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {SASL_MECHANISMS.map(mechanism => (
+                        <SelectItem key={mechanism} value={mechanism}>
+                          {mechanism}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="saslUsername">SASL username</Label>
+                  <Input
+                    id="saslUsername"
+                    value={security.sasl_username || ''}
+                    onChange={e => setSecurity({ ...security, sasl_username: e.target.value })}
+                  />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="saslPassword">SASL password</Label>
+                  <Input
+                    id="saslPassword"
+                    type="password"
+                    value={security.sasl_password || ''}
+                    onChange={e => setSecurity({ ...security, sasl_password: e.target.value })}
+                  />
+                </div>
+              </>
+            )}
+            {usesSsl && (
+              <>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="sslCaLocation">CA bundle (optional)</Label>
+                  <Input
+                    id="sslCaLocation"
+                    placeholder="/path/to/ca.pem"
+                    value={security.ssl_ca_location || ''}
+                    onChange={e => setSecurity({ ...security, ssl_ca_location: e.target.value })}
+                  />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="sslCertificateLocation">Client certificate (optional)</Label>
+                  <Input
+                    id="sslCertificateLocation"
+                    placeholder="/path/to/client.pem"
+                    value={security.ssl_certificate_location || ''}
+                    onChange={e => setSecurity({ ...security, ssl_certificate_location: e.target.value })}
+                  />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="sslKeyLocation">Client key (optional)</Label>
+                  <Input
+                    id="sslKeyLocation"
+                    placeholder="/path/to/client.key"
+                    value={security.ssl_key_location || ''}
+                    onChange={e => setSecurity({ ...security, ssl_key_location: e.target.value })}
+                  />
+                </div>
+                <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+                  <Label htmlFor="sslKeyPassword">Client key password (optional)</Label>
+                  <Input
+                    id="sslKeyPassword"
+                    type="password"
+                    value={security.ssl_key_password || ''}
+                    onChange={e => setSecurity({ ...security, ssl_key_password: e.target.value })}
+                  />
+                </div>
+                <div className="flex items-center space-x-2 m-6">
+                  <Checkbox
+                    id="sslDisableHostnameVerification"
+                    checked={!!security.ssl_disable_hostname_verification}
+                    onCheckedChange={checked =>
+                      setSecurity({ ...security, ssl_disable_hostname_verification: checked === true })
+                    }
+                  />
+                  <label
+                    htmlFor="sslDisableHostnameVerification"
+                    className="cursor-pointer text-sm font-medium leading-none"
+                  >
+                    Disable hostname verification
+                  </label>
+                </div>
+              </>
+            )}
+            <div className="grid w-full max-w-sm items-center gap-1.5 m-6">
+              <Label htmlFor="properties">Client properties (optional)</Label>
+              <Textarea
+                id="properties"
+                placeholder={'client.id=komprender\nfetch.max.bytes=52428800'}
+                value={properties}
+                onChange={e => setProperties(e.target.value)}
+              />
+              <div className="flex space-x-2">
+                <Input
+                  placeholder="/path/to/client.properties"
+                  value={propertiesFile}
+                  onChange={e => setPropertiesFile(e.target.value)}
+                />
+                <Button variant="outline" disabled={!propertiesFile} onClick={importPropertiesFile}>
+                  Import
+                </Button>
+              </div>
+            </div>
+            <Button className="m-6" disabled={connecting} onClick={connectNew}>
               Connect
             </Button>
           </div>
@@ -116,6 +671,112 @@ export function Connect({
           </div>
         )}
       </div>
+      <Dialog open={!!pendingConnect} onOpenChange={open => !open && setPendingConnect(null)}>
+        <DialogContent>
+          <DialogHeader>
+            <DialogTitle>{vault.exists ? 'Unlock credential vault' : 'Create credential vault'}</DialogTitle>
+            <DialogDescription>
+              {pendingConnect?.savedConnectionId
+                ? 'The credentials of this connection are stored in the vault.'
+                : 'Credentials are saved encrypted with this passphrase. It is asked once per session.'}
+            </DialogDescription>
+          </DialogHeader>
+          <div className="grid w-full items-center gap-1.5">
+            <Label htmlFor="vaultPassphrase">Passphrase</Label>
+            <Input
+              id="vaultPassphrase"
+              type="password"
+              value={passphrase}
+              onChange={e => setPassphrase(e.target.value)}
+              onKeyDown={e => e.key === 'Enter' && unlockAndConnect()}
+            />
+            {vaultError && <p className="text-sm text-destructive">{vaultError}</p>}
+          </div>
+          <DialogFooter>
+            {!pendingConnect?.savedConnectionId && (
+              <Button variant="outline" onClick={connectWithoutSavingCredentials}>
+                Connect without saving credentials
+              </Button>
+            )}
+            <Button disabled={!passphrase} onClick={unlockAndConnect}>
+              {vault.exists ? 'Unlock' : 'Create'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+      <Dialog open={!!savedAction} onOpenChange={open => !open && setSavedAction(null)}>
+        <DialogContent>
+          <DialogHeader>
+            <DialogTitle>
+              {savedAction === 'edit'
+                ? `Edit ${draft?.name}`
+                : savedAction === 'rename'
+                  ? `Rename ${draft?.name}`
+                  : savedAction === 'tags'
+                    ? `Tags of ${draft?.name}`
+                    : `Delete ${draft?.name}`}
+            </DialogTitle>
+            {savedAction === 'delete' && (
+              <DialogDescription>The connection and its saved credentials will be removed.</DialogDescription>
+            )}
+          </DialogHeader>
+          {draft && savedAction === 'edit' && (
+            <>
+              <div className="grid w-full items-center gap-1.5">
+                <Label htmlFor="editBrokers">Kafka brokers</Label>
+                <Input
+                  id="editBrokers"
+                  value={draft.kafka_broker}
+                  onChange={e => setDraft({ ...draft, kafka_broker: e.target.value })}
+                />
+              </div>
+              <div className="grid w-full items-center gap-1.5">
+                <Label htmlFor="editSchemaRegistry">Schema Registry (optional)</Label>
+                <Input
+                  id="editSchemaRegistry"
+                  value={draft.schema_registry || ''}
+                  onChange={e => setDraft({ ...draft, schema_registry: e.target.value })}
+                />
+              </div>
+              <div className="grid w-full items-center gap-1.5">
+                <Label htmlFor="editProperties">Client properties (optional)</Label>
+                <Textarea id="editProperties" value={draftText} onChange={e => setDraftText(e.target.value)} />
+              </div>
+            </>
+          )}
+          {draft && savedAction === 'rename' && (
+            <div className="grid w-full items-center gap-1.5">
+              <Label htmlFor="renameConnection">Connection Name</Label>
+              <Input
+                id="renameConnection"
+                value={draft.name}
+                onChange={e => setDraft({ ...draft, name: e.target.value })}
+                onKeyDown={e => e.key === 'Enter' && applySavedAction()}
+              />
+            </div>
+          )}
+          {savedAction === 'tags' && (
+            <div className="grid w-full items-center gap-1.5">
+              <Label htmlFor="connectionTags">Tags (comma separated)</Label>
+              <Input
+                id="connectionTags"
+                placeholder="prod, team-a"
+                value={draftText}
+                onChange={e => setDraftText(e.target.value)}
+                onKeyDown={e => e.key === 'Enter' && applySavedAction()}
+              />
+            </div>
+          )}
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setSavedAction(null)}>
+              Cancel
+            </Button>
+            <Button variant={savedAction === 'delete' ? 'destructive' : 'default'} onClick={applySavedAction}>
+              {savedAction === 'delete' ? 'Delete' : 'Save'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
     </>
   );
 }
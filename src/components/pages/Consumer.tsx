@@ -6,21 +6,181 @@ import { listen } from '@tauri-apps/api/event';
 import { Accordion, AccordionContent, AccordionItem, AccordionTrigger } from '@/components/ui/accordion.tsx';
 import { Table, TableBody, TableCell, TableHead, TableHeader, TableRow } from '@/components/ui/table.tsx';
 import { toast } from 'sonner';
+import { useSettings } from '@/components/misc/SettingsProvider.tsx';
+import { Input } from '@/components/ui/input.tsx';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select.tsx';
 
-interface IMessage {
+export interface IMessageHeader {
   key: string;
+  value: string | null;
+  encoding: 'string' | 'base64';
+}
+
+interface ISchemaInfo {
+  id: number;
+  subject: string | null;
+  version: number | null;
+}
+
+type DataFormat =
+  | 'null'
+  | 'text'
+  | 'json'
+  | 'avro'
+  | 'protobuf'
+  | 'json_schema'
+  | 'msgpack'
+  | 'cbor'
+  | 'hex'
+  | 'base64'
+  | 'integer'
+  | 'uuid';
+
+type DeserializerKind =
+  | 'auto'
+  | 'string'
+  | 'json'
+  | 'avro'
+  | 'protobuf'
+  | 'json_schema'
+  | 'msgpack'
+  | 'cbor'
+  | 'hex'
+  | 'base64'
+  | 'int'
+  | 'uuid';
+
+interface ITopicDeserializers {
+  key: DeserializerKind;
+  value: DeserializerKind;
+}
+
+const DESERIALIZERS: { value: DeserializerKind; label: string }[] = [
+  { value: 'auto', label: 'Auto-detect' },
+  { value: 'string', label: 'String' },
+  { value: 'json', label: 'JSON' },
+  { value: 'avro', label: 'Avro' },
+  { value: 'protobuf', label: 'Protobuf' },
+  { value: 'json_schema', label: 'JSON Schema' },
+  { value: 'msgpack', label: 'MessagePack' },
+  { value: 'cbor', label: 'CBOR' },
+  { value: 'hex', label: 'Hex' },
+  { value: 'base64', label: 'Base64' },
+  { value: 'int', label: 'Integer (big-endian)' },
+  { value: 'uuid', label: 'UUID (binary)' },
+];
+
+interface IMessage {
+  key: object | string | null;
+  key_format: DataFormat;
+  key_content_type: string | null;
+  tombstone: boolean;
   offset: number;
   partition: number;
-  value: object;
+  value: object | string | null;
+  value_format: DataFormat;
+  value_content_type: string | null;
+  key_schema: ISchemaInfo | null;
+  key_error: string | null;
+  value_schema: ISchemaInfo | null;
+  value_error: string | null;
+  headers: IMessageHeader[];
+}
+
+interface IMessageFilter {
+  key_regex: string;
+  value_contains: string;
+  value_json_path: string;
+}
+
+type ConsumeMode = 'end' | 'beginning' | 'last' | 'timestamp' | 'relative' | 'range';
+
+interface IPartitionRange {
+  partition: string;
+  start_offset: string;
+  end_offset: string;
+}
+
+interface IConsumeLimits {
+  max_messages: string;
+  max_messages_per_partition: string;
+  max_bytes: string;
+  timeout_ms: string;
+}
+
+const LIMITS: { field: keyof IConsumeLimits; placeholder: string }[] = [
+  { field: 'max_messages', placeholder: 'Max messages' },
+  { field: 'max_messages_per_partition', placeholder: 'Max messages per partition' },
+  { field: 'max_bytes', placeholder: 'Max bytes' },
+  { field: 'timeout_ms', placeholder: 'Timeout (ms)' },
+];
+
+interface IConsumeProgress {
+  topic: string;
+  scanned: number;
+  matched: number;
+}
+
+function optionalNumber(value: string) {
+  return value.trim() === '' ? null : Number(value);
+}
+
+function describeSchema(label: string, schema: ISchemaInfo | null) {
+  if (!schema) {
+    return null;
+  }
+
+  const subject = schema.subject ? `${schema.subject} v${schema.version}` : 'unknown subject';
+  return `${label} schema #${schema.id} (${subject})`;
 }
 
 export function Consumer(props: { topic: string }) {
   const [fromBeginning, setFromBeginning] = useState<boolean>(false);
   const [consumeLastX, setConsumeLastX] = useState<boolean>(false);
   const [newMessages, setNewMessages] = useState<boolean>(true);
-  const [mode, setMode] = useState<'end' | 'beginning' | 'last'>('end');
+  const [mode, setMode] = useState<ConsumeMode>('end');
+  const [fromTimestamp, setFromTimestamp] = useState<string>('');
+  const [relativeMinutes, setRelativeMinutes] = useState<string>('15');
+  const [ranges, setRanges] = useState<IPartitionRange[]>([{ partition: '0', start_offset: '0', end_offset: '' }]);
+  const [limits, setLimits] = useState<IConsumeLimits>({
+    max_messages: '',
+    max_messages_per_partition: '',
+    max_bytes: '',
+    timeout_ms: '',
+  });
   const [messages, setMessages] = useState<IMessage[]>([]);
   const [consuming, setConsuming] = useState<boolean>(false);
+  const [deserializers, setDeserializers] = useState<ITopicDeserializers>({ key: 'auto', value: 'auto' });
+  const [filter, setFilter] = useState<IMessageFilter>({ key_regex: '', value_contains: '', value_json_path: '' });
+  const [progress, setProgress] = useState<IConsumeProgress | null>(null);
+  const { settings } = useSettings();
+
+  useEffect(() => {
+    (async () => {
+      try {
+        const saved = await invoke<ITopicDeserializers>('fetch_topic_deserializers', {
+          connectionId: settings.connectionId,
+          topic: props.topic,
+        });
+        setDeserializers(saved);
+      } catch (err) {
+        toast.error('Error fetching deserializers: ' + err);
+      }
+    })();
+  }, [props.topic]);
+
+  async function changeDeserializers(changed: ITopicDeserializers) {
+    setDeserializers(changed);
+    try {
+      await invoke('save_topic_deserializers', {
+        connectionId: settings.connectionId,
+        topic: props.topic,
+        deserializers: changed,
+      });
+    } catch (err) {
+      toast.error('Error saving deserializers: ' + err);
+    }
+  }
 
   useEffect(() => {
     return () => {
@@ -36,10 +196,25 @@ export function Consumer(props: { topic: string }) {
           setMessages(messages => [event.payload, ...messages].splice(0, 100));
         }
       });
+      const unsubscribeProgress = await listen<IConsumeProgress>('consume_progress', event => {
+        if (isActive && event.payload.topic === props.topic) {
+          setProgress(event.payload);
+        }
+      });
+      const unsubscribeFinished = await listen<{ topic: string; scanned: number; messages: number }>(
+        'consume_finished',
+        event => {
+          if (isActive && event.payload.topic === props.topic) {
+            setProgress({ topic: event.payload.topic, scanned: event.payload.scanned, matched: event.payload.messages });
+          }
+        },
+      );
 
       return () => {
         isActive = false;
         unsubscribe();
+        unsubscribeProgress();
+        unsubscribeFinished();
       };
     })();
   }, []);
@@ -65,21 +240,55 @@ export function Consumer(props: { topic: string }) {
       setNewMessages(false);
       return;
     }
+
+    setFromBeginning(false);
+    setConsumeLastX(false);
+    setNewMessages(false);
   }, [mode]);
 
+  const messagesLimit = optionalNumber(limits.max_messages) ?? 100;
   const label = fromBeginning
     ? 'Consume from beginning'
     : consumeLastX
-      ? `Consume last 100 messages`
-      : 'Consume new messages';
+      ? `Consume last ${messagesLimit} messages`
+      : mode === 'timestamp'
+        ? 'Consume from timestamp'
+        : mode === 'relative'
+          ? `Consume last ${relativeMinutes} minutes`
+          : mode === 'range'
+            ? 'Consume offset ranges'
+            : 'Consume new messages';
+
+  function changeRange(index: number, changed: Partial<IPartitionRange>) {
+    setRanges(ranges.map((range, i) => (i === index ? { ...range, ...changed } : range)));
+  }
 
   async function consume() {
     setMessages([]);
+    setProgress(null);
     setConsuming(true);
     try {
       await invoke('consume_messages', {
+        connectionId: settings.connectionId,
         topic: props.topic,
         mode,
+        fromTimestamp: fromTimestamp ? new Date(fromTimestamp).getTime() : null,
+        relativeDurationMs: (optionalNumber(relativeMinutes) ?? 0) * 60 * 1000,
+        partitions:
+          mode === 'range'
+            ? ranges.map(range => ({
+                partition: Number(range.partition),
+                start_offset: Number(range.start_offset),
+                end_offset: optionalNumber(range.end_offset),
+              }))
+            : null,
+        limits: {
+          max_messages: optionalNumber(limits.max_messages),
+          max_messages_per_partition: optionalNumber(limits.max_messages_per_partition),
+          max_bytes: optionalNumber(limits.max_bytes),
+          timeout_ms: optionalNumber(limits.timeout_ms),
+        },
+        filter,
       });
     } catch (err) {
       toast.error('Error fetching saved brokers: ' + err);
@@ -90,7 +299,7 @@ export function Consumer(props: { topic: string }) {
 
   async function stop() {
     try {
-      await invoke('stop_consumers');
+      await invoke('stop_consumers', { connectionId: settings.connectionId });
       setConsuming(false);
     } catch (err) {
       toast.error('Error stopping consumers: ' + err);
@@ -106,7 +315,7 @@ export function Consumer(props: { topic: string }) {
             htmlFor="from-beginning"
             className="cursor-pointer text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70"
           >
-            Consume first 100 messages (once)
+            Consume first {messagesLimit} messages (once)
           </label>
         </div>
         <div className="flex items-center space-x-2 w-full mb-6">
@@ -124,9 +333,146 @@ export function Consumer(props: { topic: string }) {
             htmlFor="consume-last-x"
             className="flex items-center cursor-pointer text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70 flex-grow"
           >
-            <span>Consume last 100 messages (continuous) *can be slow for large amount of partitions*</span>
+            <span>Consume last {messagesLimit} messages (once) *can be slow for large amount of partitions*</span>
           </label>
         </div>
+        <div className="flex items-center space-x-2 w-full mb-6">
+          <Checkbox id="from-timestamp" onCheckedChange={() => setMode('timestamp')} checked={mode === 'timestamp'} />
+          <label
+            htmlFor="from-timestamp"
+            className="cursor-pointer text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70"
+          >
+            Consume from timestamp (continuous)
+          </label>
+          {mode === 'timestamp' && (
+            <Input
+              type="datetime-local"
+              className="w-64"
+              value={fromTimestamp}
+              onInput={(e: any) => setFromTimestamp(e.target.value)}
+            />
+          )}
+        </div>
+        <div className="flex items-center space-x-2 w-full mb-6">
+          <Checkbox id="relative" onCheckedChange={() => setMode('relative')} checked={mode === 'relative'} />
+          <label
+            htmlFor="relative"
+            className="cursor-pointer text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70"
+          >
+            Consume messages of the last minutes (continuous)
+          </label>
+          {mode === 'relative' && (
+            <Input
+              type="number"
+              min={1}
+              className="w-32"
+              value={relativeMinutes}
+              onInput={(e: any) => setRelativeMinutes(e.target.value)}
+              placeholder="Minutes"
+            />
+          )}
+        </div>
+        <div className="flex items-center space-x-2 w-full mb-6">
+          <Checkbox id="range" onCheckedChange={() => setMode('range')} checked={mode === 'range'} />
+          <label
+            htmlFor="range"
+            className="cursor-pointer text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70"
+          >
+            Consume offset ranges of partitions (open-ended ranges stop at the high watermark)
+          </label>
+        </div>
+        {mode === 'range' && (
+          <div className="w-full mb-6 space-y-2">
+            {ranges.map((range, i) => (
+              <div key={'range' + i} className="flex space-x-4">
+                <Input
+                  type="number"
+                  min={0}
+                  value={range.partition}
+                  onInput={(e: any) => changeRange(i, { partition: e.target.value })}
+                  placeholder="Partition"
+                />
+                <Input
+                  type="number"
+                  min={0}
+                  value={range.start_offset}
+                  onInput={(e: any) => changeRange(i, { start_offset: e.target.value })}
+                  placeholder="Start offset"
+                />
+                <Input
+                  type="number"
+                  min={0}
+                  value={range.end_offset}
+                  onInput={(e: any) => changeRange(i, { end_offset: e.target.value })}
+                  placeholder="End offset (inclusive, optional)"
+                />
+                <Button
+                  variant="outline"
+                  disabled={ranges.length === 1}
+                  onClick={() => setRanges(ranges.filter((_, j) => j !== i))}
+                >
+                  Remove
+                </Button>
+              </div>
+            ))}
+            <Button
+              variant="outline"
+              onClick={() => setRanges([...ranges, { partition: '', start_offset: '0', end_offset: '' }])}
+            >
+              Add partition
+            </Button>
+          </div>
+        )}
+        <div className="flex space-x-4 w-full mb-6">
+          {LIMITS.map(limit => (
+            <Input
+              key={limit.field}
+              type="number"
+              min={1}
+              value={limits[limit.field]}
+              onInput={(e: any) => setLimits({ ...limits, [limit.field]: e.target.value })}
+              placeholder={limit.placeholder}
+            />
+          ))}
+        </div>
+        <div className="flex space-x-4 w-full mb-6">
+          {(['key', 'value'] as const).map(part => (
+            <Select
+              key={part}
+              value={deserializers[part]}
+              onValueChange={value => changeDeserializers({ ...deserializers, [part]: value as DeserializerKind })}
+            >
+              <SelectTrigger>
+                <span className="mr-2 text-muted-foreground">{part === 'key' ? 'Key' : 'Value'}:</span>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {DESERIALIZERS.map(deserializer => (
+                  <SelectItem key={deserializer.value} value={deserializer.value}>
+                    {deserializer.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          ))}
+        </div>
+        <div className="flex space-x-4 w-full mb-6">
+          <Input
+            value={filter.key_regex}
+            onInput={(e: any) => setFilter({ ...filter, key_regex: e.target.value })}
+            placeholder="Key regex"
+          />
+          <Input
+            value={filter.value_contains}
+            onInput={(e: any) => setFilter({ ...filter, value_contains: e.target.value })}
+            placeholder="Value contains"
+          />
+          <Input
+            value={filter.value_json_path}
+            onInput={(e: any) => setFilter({ ...filter, value_json_path: e.target.value })}
+            placeholder="JSONPath, e.g. $.items[?@.price > 10]"
+          />
+        </div>
         <div className="space-x-4">
           <Button disabled={consuming} onClick={() => consume()}>
             {label}
@@ -134,6 +480,11 @@ export function Consumer(props: { topic: string }) {
           <Button disabled={!consuming} onClick={() => stop()}>
             Stop consuming
           </Button>
+          {progress && (
+            <span className="text-sm text-muted-foreground">
+              {progress.matched} matched of {progress.scanned} scanned
+            </span>
+          )}
         </div>
         <Accordion type="single" collapsible className="w-full mt-6 border border-b-0">
           <div className="flex">
@@ -156,12 +507,26 @@ export function Consumer(props: { topic: string }) {
                     <TableRow key={'message' + i}>
                       <TableCell className="w-[33%] text-left">{message.partition}</TableCell>
                       <TableCell className="w-[33%] text-left">{message.offset}</TableCell>
-                      <TableCell className="w-[33%] text-left">{message.key}</TableCell>
+                      <TableCell className="w-[33%] text-left">
+                        {typeof message.key === 'object' ? JSON.stringify(message.key) : message.key}
+                      </TableCell>
                     </TableRow>
                   </TableBody>
                 </Table>
               </AccordionTrigger>
               <AccordionContent className="p-2">
+                {[
+                  describeSchema('Key', message.key_schema),
+                  describeSchema('Value', message.value_schema),
+                  message.key_error && `Key could not be deserialized: ${message.key_error}`,
+                  message.value_error && `Value could not be deserialized: ${message.value_error}`,
+                ]
+                  .filter(Boolean)
+                  .map(description => (
+                    <p key={description} className="text-xs text-muted-foreground mb-2">
+                      {description}
+                    </p>
+                  ))}
                 <div className="flex items-center space-x-2">
                   <p className="text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70">
                     <pre>{JSON.stringify(message.value, null, 2)}</pre>
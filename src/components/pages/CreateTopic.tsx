@@ -10,6 +10,7 @@ import { useNavigate } from 'react-router-dom';
 import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert.tsx';
 import { AlertCircle } from 'lucide-react';
 import { useState } from 'react';
+import { useSettings } from '@/components/misc/SettingsProvider.tsx';
 
 const formSchema = z.object({
   name: z.string().min(2, {
@@ -35,6 +36,7 @@ const formSchema = z.object({
 export function CreateTopic() {
   const navigate = useNavigate();
   const [error, setError] = useState<string>('');
+  const { settings } = useSettings();
   const form = useForm<z.infer<typeof formSchema>>({
     resolver: zodResolver(formSchema),
     mode: 'onSubmit',
@@ -52,6 +54,7 @@ export function CreateTopic() {
   async function onSubmit(values: z.infer<typeof formSchema>) {
     try {
       await invoke('create_topic', {
+        connectionId: settings.connectionId,
         topic: {
           cleanup_policy: values.cleanupPolicy,
           insync_replicas: values.insyncReplicas,
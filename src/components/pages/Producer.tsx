@@ -19,6 +19,15 @@ import {
 } from '@/components/ui/command.tsx';
 import { cn } from '@/lib/utils.ts';
 
+interface IDeliveryReport {
+  topic: string;
+  partition: number;
+  offset: number;
+  timestamp: number;
+  serialized_key_size: number;
+  serialized_value_size: number;
+}
+
 export function Producer(props: { topic: string; setProduced: Function }) {
   const [open, setOpen] = useState(false);
   const [search, setSearch] = useState('');
@@ -55,13 +64,19 @@ export function Producer(props: { topic: string; setProduced: Function }) {
     const action = state === 'avro' ? 'produce_message_avro' : 'produce_message_json';
     const payload =
       state === 'avro'
-        ? { topic: props.topic, payload: cleanedMessage, schemaName: selectedSubject, key: cleanedKey }
-        : { topic: props.topic, payload: cleanedMessage, key: cleanedKey };
+        ? {
+            connectionId: settings.connectionId,
+            topic: props.topic,
+            payload: cleanedMessage,
+            schemaName: selectedSubject,
+            key: cleanedKey,
+          }
+        : { connectionId: settings.connectionId, topic: props.topic, payload: cleanedMessage, key: cleanedKey };
 
     try {
       setIsLoading(true);
-      await invoke(action, payload);
-      toast.success('Message has been sent');
+      const report = await invoke<IDeliveryReport>(action, payload);
+      toast.success(`Message has been sent to partition ${report.partition} at offset ${report.offset}`);
       props.setProduced((prev: number) => prev + 1);
     } catch (err) {
       if (typeof err === 'string') {
@@ -82,7 +97,7 @@ export function Producer(props: { topic: string; setProduced: Function }) {
 
   async function selectSchema(subject: string) {
     try {
-      const schemaData = await invoke<string>('fetch_schema', { subject });
+      const schemaData = await invoke<string>('fetch_schema', { connectionId: settings.connectionId, subject });
       setSelectedSubject(subject);
       setSchema(JSON.stringify(JSON.parse(schemaData), undefined, 2));
     } catch (err) {
@@ -102,7 +117,7 @@ export function Producer(props: { topic: string; setProduced: Function }) {
     }
     (async () => {
       try {
-        const subjects = await invoke<string[]>('fetch_sr_subjects');
+        const subjects = await invoke<string[]>('fetch_sr_subjects', { connectionId: settings.connectionId });
         setSubjects(subjects);
       } catch {
         toast.error('Error fetching schema subjects');
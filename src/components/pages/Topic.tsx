@@ -7,6 +7,7 @@ import { Button } from '@/components/ui/button.tsx';
 import { Producer } from '@/components/pages/Producer.tsx';
 import { Consumer } from '@/components/pages/Consumer.tsx';
 import { toast } from 'sonner';
+import { useSettings } from '@/components/misc/SettingsProvider.tsx';
 
 interface IPartition {
   id: number;
@@ -27,10 +28,11 @@ export function Topic() {
   const [topic, setTopic] = useState<ITopicInfo | null>(null);
   const [produced, setProduced] = useState(0);
   const [state, setState] = useState<'partitions' | 'producer' | 'consumer'>('partitions');
+  const { settings } = useSettings();
 
   async function fetchTopic() {
     try {
-      const response = await invoke<ITopicInfo>('fetch_topic', { name });
+      const response = await invoke<ITopicInfo>('fetch_topic', { connectionId: settings.connectionId, name });
       setTopic(response);
     } catch (err) {
       toast.error('Error fetching topic data: ' + err);
@@ -9,6 +9,7 @@ import { useNavigate } from 'react-router-dom';
 import { Plus } from 'lucide-react';
 import { Skeleton } from '@/components/ui/skeleton.tsx';
 import { toast } from 'sonner';
+import { useSettings } from '@/components/misc/SettingsProvider.tsx';
 
 interface ITopic {
   name: string;
@@ -22,10 +23,11 @@ export function Topics({ disconnect }: { disconnect: () => void }) {
   const [checkedTopics, setCheckedTopics] = useState<string[]>([]);
   const [checkedAll, setCheckedAll] = useState(false);
   const navigate = useNavigate();
+  const { settings } = useSettings();
 
   const fetchTopics = _.debounce(async (i = 0) => {
     try {
-      const response = await invoke<ITopic[]>('fetch_topics', { filter });
+      const response = await invoke<ITopic[]>('fetch_topics', { connectionId: settings.connectionId, filter });
       setTopics(response);
     } catch (err) {
       if (i > 5) {
@@ -76,7 +78,7 @@ export function Topics({ disconnect }: { disconnect: () => void }) {
   function dropTopics() {
     (async () => {
       try {
-        await invoke('drop_topics', { topicNames: checkedTopics });
+        await invoke('drop_topics', { connectionId: settings.connectionId, topicNames: checkedTopics });
         fetchTopics();
       } catch (err) {
         toast.error('Error dropping topics: ' + err);